@@ -0,0 +1,96 @@
+use std::{hint::black_box, num::NonZeroUsize};
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use tsg2425::{
+    corners::{self, DataSource},
+    Fixture, Teams,
+};
+
+const SEASONS: [u32; 3] = [1, 5, 20];
+
+/// A double round robin of every registered team per season, by the circle method.
+/// Game weeks carry on from one season to the next so every fixture stays unique.
+fn schedule(seasons: u32) -> Vec<(u32, Teams, Teams)> {
+    let mut teams = Teams::all().map(Some).collect::<Vec<_>>();
+    if teams.len() % 2 == 1 {
+        teams.push(None);
+    }
+    let rounds = teams.len() as u32 - 1;
+
+    let mut matches = Vec::new();
+    for season in 0..seasons {
+        let mut order = teams.clone();
+        for round in 0..rounds {
+            let first_leg = season * 2 * rounds + round + 1;
+            for i in 0..order.len() / 2 {
+                if let (Some(home), Some(away)) = (order[i], order[order.len() - 1 - i]) {
+                    matches.push((first_leg, home, away));
+                    matches.push((first_leg + rounds, away, home));
+                }
+            }
+            order[1..].rotate_right(1);
+        }
+    }
+    matches
+}
+
+/// Two corner rows per match, with made-up but varied numbers.
+fn corner_rows(seasons: u32) -> Vec<DataSource> {
+    schedule(seasons)
+        .into_iter()
+        .enumerate()
+        .flat_map(|(idx, (game_week, home, away))| {
+            let game = Fixture::new(home, away);
+            [home, away].map(|team| {
+                let seed = (idx as u32 * 7 + team.name().len() as u32) % 11;
+                DataSource {
+                    row: idx as u64 * 2 + 2,
+                    team,
+                    game: game.clone(),
+                    game_week,
+                    total_ck_for: seed,
+                    shots_from_ck: seed / 3,
+                    xg: seed as f64 * 0.013,
+                }
+            })
+        })
+        .collect()
+}
+
+fn join(c: &mut Criterion) {
+    let mut group = c.benchmark_group("corners/join");
+    for seasons in SEASONS {
+        let rows = corner_rows(seasons);
+        group.throughput(Throughput::Elements(rows.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(seasons), &rows, |b, rows| {
+            b.iter_batched(
+                || rows.clone(),
+                |rows| corners::join(black_box(rows)).expect("synthetic rows pair up"),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn aggregate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("corners/aggregate");
+    for seasons in SEASONS {
+        let rows = corner_rows(seasons);
+        group.throughput(Throughput::Elements(rows.len() as u64));
+        let data = corners::join(rows).expect("synthetic rows pair up");
+        group.bench_with_input(BenchmarkId::new("totals", seasons), &data, |b, data| {
+            b.iter(|| {
+                let matches = corners::match_frame(black_box(data)).expect("columns line up");
+                corners::totals(&matches).expect("columns exist")
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("rolling", seasons), &data, |b, data| {
+            b.iter(|| corners::rolling(black_box(data), NonZeroUsize::new(5).unwrap()))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, join, aggregate);
+criterion_main!(benches);
@@ -0,0 +1,281 @@
+//! The dataset exports as Polars frames, for prototyping metrics with group-bys, joins
+//! and `fill_null` before they get a chart. Needs the `polars` feature.
+//!
+//! Team names are resolved through the team registry, so `team` and `opponent` always
+//! hold the registry's spelling and join cleanly.
+
+use std::{collections::HashMap, path::Path};
+
+use polars::prelude::{
+    col, Column as PlColumn, DataFrame as PlFrame, DataType, Expr, Field, IntoLazy, JoinArgs,
+    JoinType, LazyCsvReader, LazyFileListReader, LazyFrame, NullValues, PlPathRef, Schema,
+    SchemaRef,
+};
+
+use crate::{
+    corners::CornerKickData, Column, DataFrame, Fixture, MyError, Result, ResultExt, Teams, Venue,
+};
+
+/// Value columns of a corner export, joined as `{column}_against`.
+pub const CORNER_VALUES: [&str; 3] = ["total_ck_for", "xg", "shots_from_ck"];
+/// Value columns of a game-state export, joined as `{column}_against`.
+pub const GAME_STATE_VALUES: [&str; 3] = ["winning", "drawing", "losing"];
+
+/// `team,game,game_week,total_ck_for,xg,shots_from_ck`.
+pub fn corner_schema() -> Schema {
+    Schema::from_iter([
+        Field::new("team".into(), DataType::String),
+        Field::new("game".into(), DataType::String),
+        Field::new("game_week".into(), DataType::UInt32),
+        Field::new("total_ck_for".into(), DataType::UInt32),
+        Field::new("xg".into(), DataType::Float64),
+        Field::new("shots_from_ck".into(), DataType::UInt32),
+    ])
+}
+
+/// `Team,Match,Winning,Drawing,Losing`, where `-` is null.
+pub fn game_state_schema() -> Schema {
+    Schema::from_iter([
+        Field::new("Team".into(), DataType::String),
+        Field::new("Match".into(), DataType::String),
+        Field::new("Winning".into(), DataType::Float64),
+        Field::new("Drawing".into(), DataType::Float64),
+        Field::new("Losing".into(), DataType::Float64),
+    ])
+}
+
+fn read_csv(path: &Path, schema: Schema) -> Result<PlFrame> {
+    Ok(
+        LazyCsvReader::new(PlPathRef::from_local_path(path).into_owned())
+            .with_schema(Some(SchemaRef::new(schema)))
+            .with_null_values(Some(NullValues::AllColumnsSingle("-".into())))
+            .finish()?
+            .collect()?,
+    )
+}
+
+/// A corner export with `opponent` and `venue` (`home` or `away`) added.
+pub fn read_corners<P: AsRef<Path>>(path: P) -> Result<PlFrame> {
+    let path = path.as_ref();
+    let df = read_csv(path, corner_schema()).with_path(path)?;
+    with_opponents(df, "team", "game").with_path(path)
+}
+
+/// A game-state export with its columns lower-cased, and `opponent` and `venue` added.
+pub fn read_game_state<P: AsRef<Path>>(path: P) -> Result<PlFrame> {
+    let path = path.as_ref();
+    let mut df = read_csv(path, game_state_schema()).with_path(path)?;
+    df.set_column_names(["team", "match", "winning", "drawing", "losing"])
+        .with_path(path)?;
+    with_opponents(df, "team", "match").with_path(path)
+}
+
+/// Rewrites `team` to the registry's names and adds the columns of the team's fixture.
+fn with_opponents(mut df: PlFrame, team: &str, fixture: &str) -> Result<PlFrame> {
+    let rows = df
+        .column(team)?
+        .str()?
+        .into_iter()
+        .zip(df.column(fixture)?.str()?)
+        .enumerate()
+        .map(|(idx, (team, fixture))| {
+            let (Some(team), Some(fixture)) = (team, fixture) else {
+                return Ok((None, None, None));
+            };
+            let team = team.parse::<Teams>()?;
+            let fixture = Fixture::parse(fixture)?;
+            let (opponent, venue) = match fixture.venue_of(team) {
+                Some(Venue::Home) => (fixture.away, "home"),
+                Some(Venue::Away) => (fixture.home, "away"),
+                None => {
+                    return Err(MyError::UnmatchedFixture {
+                        path: None,
+                        row: idx as u64 + 2,
+                        team,
+                        fixture,
+                        opponent: None,
+                    })
+                }
+            };
+            Ok((Some(team.name()), Some(opponent.name()), Some(venue)))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let (teams, rest): (Vec<_>, Vec<_>) = rows.into_iter().map(|(t, o, v)| (t, (o, v))).unzip();
+    let (opponents, venues): (Vec<_>, Vec<_>) = rest.into_iter().unzip();
+    df.with_column(PlColumn::new(team.into(), teams))?;
+    df.with_column(PlColumn::new("opponent".into(), opponents))?;
+    df.with_column(PlColumn::new("venue".into(), venues))?;
+
+    Ok(df)
+}
+
+/// Left-joins every row with the opponent's row that has the same `keys`, adding each of
+/// `values` as `{value}_against`. Missing opponent rows give nulls.
+pub fn join_opponents(df: LazyFrame, keys: &[&str], values: &[&str]) -> LazyFrame {
+    let theirs = df.clone().select(
+        keys.iter()
+            .map(|key| col(*key))
+            .chain([col("team").alias("opponent")])
+            .chain(
+                values
+                    .iter()
+                    .map(|value| col(*value).alias(format!("{value}_against"))),
+            )
+            .collect::<Vec<_>>(),
+    );
+    let on = keys
+        .iter()
+        .chain(&["opponent"])
+        .map(|key| col(*key))
+        .collect::<Vec<Expr>>();
+
+    df.join(theirs, &on, &on, JoinArgs::new(JoinType::Left))
+}
+
+/// [`read_corners`] joined with the opponent's corners of the same match.
+pub fn paired_corners<P: AsRef<Path>>(path: P) -> Result<PlFrame> {
+    let path = path.as_ref();
+    let df = read_corners(path)?;
+    join_opponents(df.lazy(), &["game", "game_week"], &CORNER_VALUES)
+        .collect()
+        .with_path(path)
+}
+
+/// [`read_game_state`] joined with the opponent's values for the same match.
+///
+/// Unlike [`GameStateSeries::paired`](crate::GameStateSeries::paired) the opponent's
+/// values are not flipped: `winning_against` is the opponent's winning column.
+pub fn paired_game_state<P: AsRef<Path>>(path: P) -> Result<PlFrame> {
+    let path = path.as_ref();
+    let df = read_game_state(path)?;
+    join_opponents(df.lazy(), &["match"], &GAME_STATE_VALUES)
+        .collect()
+        .with_path(path)
+}
+
+/// Converts to the crate's [`DataFrame`], e.g. for
+/// [`corners::attacking_chart`](crate::corners::attacking_chart) or
+/// [`StateFrame::new`](crate::StateFrame::new).
+///
+/// `team` and `opponent` become team columns and other text columns stay text. Integer
+/// columns become integers, or numbers with NaN for null when they have nulls, as after a
+/// join with missing opponent rows. Float columns become numbers with NaN for null. Other
+/// column types are an error.
+pub fn to_frame(df: &PlFrame) -> Result<DataFrame> {
+    let mut frame = DataFrame::new();
+    for column in df.get_columns() {
+        let name = column.name().as_str();
+        let converted = match column.dtype() {
+            DataType::String if matches!(name, "team" | "opponent") => Column::Team(
+                column
+                    .str()?
+                    .into_iter()
+                    .map(|team| team.unwrap_or_default().parse())
+                    .collect::<Result<_>>()?,
+            ),
+            DataType::String => Column::Text(
+                column
+                    .str()?
+                    .into_iter()
+                    .map(|text| text.unwrap_or_default().to_string())
+                    .collect(),
+            ),
+            dtype if dtype.is_integer() && column.null_count() == 0 => Column::Integer(
+                column
+                    .cast(&DataType::Int64)?
+                    .i64()?
+                    .into_no_null_iter()
+                    .collect(),
+            ),
+            dtype if dtype.is_primitive_numeric() => Column::Number(
+                column
+                    .cast(&DataType::Float64)?
+                    .f64()?
+                    .into_iter()
+                    .map(|value| value.unwrap_or(f64::NAN))
+                    .collect(),
+            ),
+            dtype => {
+                return Err(MyError::InvalidColumn(format!(
+                    "Column {name:?} has unsupported type {dtype}"
+                )))
+            }
+        };
+        frame = frame.with_column(name, converted)?;
+    }
+
+    Ok(frame)
+}
+
+/// Converts a [`paired_corners`] frame back to what
+/// [`corners::parse_csv`](crate::corners::parse_csv) returns. Rows without an opponent
+/// row are an error.
+pub fn to_corner_data(df: &PlFrame) -> Result<HashMap<u32, Vec<CornerKickData>>> {
+    let frame = to_frame(df)?;
+    let (teams, games, venues) = (
+        frame.teams("team")?,
+        frame.texts("game")?,
+        frame.texts("venue")?,
+    );
+    // Nulls, e.g. from a missing opponent row, leave a column as numbers with NaN.
+    let counts = [
+        "game_week",
+        "total_ck_for",
+        "total_ck_for_against",
+        "shots_from_ck",
+        "shots_from_ck_against",
+    ];
+    for name in counts.iter().chain(&["xg", "xg_against"]) {
+        if let Column::Number(values) = frame.column(name)? {
+            if let Some(idx) = values.iter().position(|value| value.is_nan()) {
+                return Err(MyError::InvalidColumn(format!(
+                    "Missing values for {} in {}",
+                    teams[idx], games[idx]
+                )));
+            }
+        }
+    }
+    let integers = |name: &str| frame.integers(name);
+    let (game_weeks, ck_for, ck_against) = (
+        integers("game_week")?,
+        integers("total_ck_for")?,
+        integers("total_ck_for_against")?,
+    );
+    let (shots, shots_against, xg, xg_against) = (
+        integers("shots_from_ck")?,
+        integers("shots_from_ck_against")?,
+        frame.numbers("xg")?,
+        frame.numbers("xg_against")?,
+    );
+
+    let mut weeks: HashMap<u32, Vec<CornerKickData>> = HashMap::new();
+    for idx in 0..frame.len() {
+        let game_week = game_weeks[idx] as u32;
+        let venue = match venues[idx].as_str() {
+            "home" => Venue::Home,
+            "away" => Venue::Away,
+            venue => {
+                return Err(MyError::InvalidColumn(format!(
+                    "Invalid venue {venue:?} for {} in {}",
+                    teams[idx], games[idx]
+                )))
+            }
+        };
+
+        weeks.entry(game_week).or_default().push(CornerKickData {
+            team: teams[idx],
+            fixture: Fixture::parse(&games[idx])?.with_game_week(game_week),
+            game_week,
+            venue,
+            total_ck_for: ck_for[idx] as u32,
+            total_ck_against: ck_against[idx] as u32,
+            shots_from_ck: shots[idx] as u32,
+            shots_against_from_ck: shots_against[idx] as u32,
+            xg: xg[idx],
+            xg_against: xg_against[idx],
+        });
+    }
+
+    Ok(weeks)
+}
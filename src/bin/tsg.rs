@@ -0,0 +1,573 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    fs,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+#[cfg(feature = "arrow")]
+use tsg2425::export::{self, TableFormat};
+use tsg2425::{
+    chart::{Chart, LogoSize, MetricPanel, OutputFormat, PanelReport, TrendChart},
+    corners::{self, Window},
+    game_state::{aggregate, Aggregation, Ratio},
+    metrics,
+    validate::{self, Severity},
+    DataFrame, GameStateSeries, GameWeekRange, LogoStore, MyError, Result, ResultExt, Row,
+    StateFrame, TeamRegistry, Teams,
+};
+
+#[derive(Debug, Parser)]
+#[command(name = "tsg", about = "Charts for the TSG Liga 1 exports")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    #[command(flatten)]
+    options: Options,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Attacking and defensive corner scatter plots
+    Corners {
+        #[arg(long, default_value = "dataset/xg_corner.csv")]
+        input: PathBuf,
+        /// Only each team's last N matches
+        #[arg(long, value_name = "N", conflicts_with = "half")]
+        last: Option<NonZeroUsize>,
+        /// Only the first or second half of the season
+        #[arg(long)]
+        half: Option<SeasonHalf>,
+    },
+    /// Rolling corner form per team, written as CSV
+    CornerForm {
+        #[arg(long, default_value = "dataset/xg_corner.csv")]
+        input: PathBuf,
+        /// Matches per rolling window
+        #[arg(long, default_value = "5")]
+        window: NonZeroUsize,
+    },
+    /// Corner xG for and against per game week, one line per selected team
+    CornerTrend {
+        #[arg(long, default_value = "dataset/xg_corner.csv")]
+        input: PathBuf,
+        /// Matches in the rolling average
+        #[arg(long, default_value = "3")]
+        window: NonZeroUsize,
+    },
+    /// Corner xG and xG conceded per team, in club colours
+    CornerXg {
+        #[arg(long, default_value = "dataset/xg_corner.csv")]
+        input: PathBuf,
+    },
+    /// Field tilt per game state
+    FieldTilt {
+        #[arg(long, default_value = "dataset/touchfinal3rd.csv")]
+        input: PathBuf,
+    },
+    /// PPDA per game state
+    Ppda {
+        #[arg(long, default_value = "dataset/ppda.csv")]
+        input: PathBuf,
+    },
+    /// Field tilt and PPDA side by side
+    Report {
+        #[arg(long, default_value = "dataset/touchfinal3rd.csv")]
+        field_tilt_input: PathBuf,
+        #[arg(long, default_value = "dataset/ppda.csv")]
+        ppda_input: PathBuf,
+    },
+    /// Corner matches, corner totals, field tilt and PPDA as Parquet or Arrow IPC tables
+    #[cfg(feature = "arrow")]
+    Export {
+        #[arg(long, default_value = "dataset/xg_corner.csv")]
+        corners: PathBuf,
+        #[arg(long, default_value = "dataset/touchfinal3rd.csv")]
+        field_tilt: PathBuf,
+        #[arg(long, default_value = "dataset/ppda.csv")]
+        ppda: PathBuf,
+        /// parquet or ipc
+        #[arg(long, default_value = "parquet")]
+        table_format: TableFormat,
+    },
+    /// Check the exports for missing rows and impossible values, failing on errors
+    Validate {
+        #[arg(long, default_value = "dataset/xg_corner.csv")]
+        corners: PathBuf,
+        #[arg(long, default_value = "dataset/touchfinal3rd.csv")]
+        field_tilt: PathBuf,
+        #[arg(long, default_value = "dataset/ppda.csv")]
+        ppda: PathBuf,
+    },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum SeasonHalf {
+    First,
+    Second,
+}
+
+#[derive(Debug, Args)]
+struct Options {
+    /// Directory the charts are written to
+    #[arg(long, global = true, default_value = "chart_output")]
+    out_dir: PathBuf,
+    /// Image size as WIDTHxHEIGHT, the default depends on the chart
+    #[arg(long, global = true, value_parser = parse_size)]
+    size: Option<(u32, u32)>,
+    /// png or svg
+    #[arg(long, global = true, env = "TSG_FORMAT", default_value = "png")]
+    format: OutputFormat,
+    /// Only count matches in these game weeks, e.g. 1-17, 18- or 5
+    #[arg(long, global = true)]
+    game_weeks: Option<GameWeekRange>,
+    /// Only chart this team, repeat for several
+    #[arg(long = "team", global = true)]
+    teams: Vec<String>,
+    /// Team registry file instead of the bundled one
+    #[arg(long, global = true, env = "TSG_TEAMS")]
+    registry: Option<PathBuf>,
+    /// Directory with the club logos, instead of the registry's `logo_dir`
+    #[arg(long, global = true, env = "TSG_LOGOS")]
+    logo_dir: Option<PathBuf>,
+    /// Size logos for this DPI instead of scaling them with the chart
+    #[arg(long, global = true)]
+    logo_dpi: Option<u32>,
+    /// Corner export used to look up game weeks for the game-state exports,
+    /// which have no game-week column
+    #[arg(long, global = true, default_value = "dataset/xg_corner.csv")]
+    calendar: PathBuf,
+}
+
+impl Options {
+    fn output(&self, name: &str) -> PathBuf {
+        self.out_dir
+            .join(name)
+            .with_extension(self.format.extension())
+    }
+
+    /// Writes the numbers behind chart `name` next to it, as CSV and JSON.
+    fn write_data(&self, name: &str, df: &DataFrame) -> Result<()> {
+        df.write_csv(self.out_dir.join(name).with_extension("csv"))?;
+        df.write_json(self.out_dir.join(name).with_extension("json"))
+    }
+
+    fn size_or(&self, default: (u32, u32)) -> (u32, u32) {
+        self.size.unwrap_or(default)
+    }
+
+    /// The `--team` selection, or `all` when nothing was selected. Selecting none of
+    /// `all` is an [`MyError::EmptyDataset`], so a chart is never drawn without teams.
+    fn select_teams(&self, all: Vec<Teams>) -> Result<Vec<Teams>> {
+        if self.teams.is_empty() {
+            return Ok(all);
+        }
+        let selected = self
+            .teams
+            .iter()
+            .map(|team| team.parse())
+            .collect::<Result<Vec<Teams>>>()?;
+        let teams = all
+            .into_iter()
+            .filter(|team| selected.contains(team))
+            .collect::<Vec<_>>();
+        if teams.is_empty() {
+            return Err(MyError::EmptyDataset { path: None });
+        }
+
+        Ok(teams)
+    }
+}
+
+fn parse_size(s: &str) -> std::result::Result<(u32, u32), String> {
+    let invalid = || format!("Invalid size {s:?}, expected WIDTHxHEIGHT");
+    let (width, height) = s.split_once(['x', 'X']).ok_or_else(invalid)?;
+    let width = width.trim().parse().map_err(|_| invalid())?;
+    let height = height.trim().parse().map_err(|_| invalid())?;
+    Ok((width, height))
+}
+
+/// Game week of every (home, away) pairing in the corner export.
+fn calendar<P: AsRef<Path>>(path: P) -> Result<HashMap<(Teams, Teams), u32>> {
+    let records = corners::parse_csv(path)?;
+
+    Ok(records
+        .values()
+        .flatten()
+        .filter_map(|d| Some(((d.fixture.home, d.fixture.away), d.fixture.game_week?)))
+        .collect())
+}
+
+fn load_series(path: &Path, options: &Options) -> Result<GameStateSeries<f64>> {
+    let mut series = GameStateSeries::from_csv(path)?;
+
+    if let Some(range) = options.game_weeks {
+        let calendar = calendar(&options.calendar)?;
+        series.retain(|record| {
+            calendar
+                .get(&(record.fixture.home, record.fixture.away))
+                .is_some_and(|game_week| range.contains(*game_week))
+        });
+        if series.records().is_empty() {
+            return Err(MyError::EmptyDataset {
+                path: Some(path.to_path_buf()),
+            });
+        }
+    }
+
+    Ok(series)
+}
+
+/// Per-team means of `metric` in every game state, see [`aggregate`].
+fn state_aggregates<F>(series: &GameStateSeries<f64>, metric: F) -> Result<DataFrame>
+where
+    F: Fn(Option<&f64>, Option<&f64>) -> Option<Ratio>,
+{
+    let pairs = series.paired()?;
+    aggregate(&pairs, Aggregation::Mean, metric)
+}
+
+/// Keeps the rows whose `team` is one of `teams`.
+fn of_teams(teams: &[Teams]) -> impl Fn(Row<'_>) -> bool + '_ {
+    |row| row.team("team").is_some_and(|team| teams.contains(&team))
+}
+
+fn series_teams(series: &GameStateSeries<f64>) -> Vec<Teams> {
+    let mut teams = series.teams();
+    teams.sort_by_key(|team| team.name());
+    teams
+}
+
+fn load_corners(
+    input: &Path,
+    options: &Options,
+) -> Result<HashMap<u32, Vec<corners::CornerKickData>>> {
+    let records = corners::parse_csv(input)?;
+
+    match options.game_weeks {
+        Some(range) => corners::window(records, Window::GameWeeks(range)).with_path(input),
+        None => Ok(records),
+    }
+}
+
+fn corners(input: &Path, window: Option<Window>, options: &Options) -> Result<()> {
+    let mut records = load_corners(input, options)?;
+    if let Some(window) = window {
+        records = corners::window(records, window).with_path(input)?;
+    }
+
+    let totals = corners::totals(&corners::match_frame(&records)?)?;
+    let teams = options
+        .select_teams(totals.teams("team")?.to_vec())
+        .with_path(input)?;
+    let df = totals.filter(of_teams(&teams));
+
+    let size = options.size_or((1024, 768));
+    let logo_size = options.logo_dpi.map_or(LogoSize::Auto, LogoSize::Dpi);
+    corners::attacking_chart(&df)?
+        .logo_size(logo_size)
+        .save(options.output("cka"), size)?;
+    options.write_data(
+        "cka",
+        &df.select(&[
+            "team",
+            "matches",
+            "total_ck_for",
+            "shots_from_ck",
+            "xg",
+            "shots_per_corner",
+            "xg_per_shot",
+        ])?,
+    )?;
+    corners::defensive_chart(&df)?
+        .logo_size(logo_size)
+        .save(options.output("ckd"), size)?;
+    options.write_data(
+        "ckd",
+        &df.select(&[
+            "team",
+            "matches",
+            "total_ck_against",
+            "shots_against_from_ck",
+            "xg_against",
+            "shots_against_per_corner",
+            "xg_against_per_shot",
+        ])?,
+    )?;
+
+    Ok(())
+}
+
+fn corner_form(input: &Path, window: NonZeroUsize, options: &Options) -> Result<()> {
+    let records = load_corners(input, options)?;
+    let form = corners::rolling(&records, window);
+    let teams = options
+        .select_teams(form.iter().map(|series| series.team).collect())
+        .with_path(input)?;
+
+    let path = options.out_dir.join("corner_form.csv");
+    let mut writer = csv::Writer::from_path(&path).with_path(&path)?;
+    writer.write_record([
+        "team",
+        "game_week",
+        "shots_per_corner",
+        "xg_per_shot",
+        "shots_against_per_corner",
+        "xg_against_per_shot",
+    ])?;
+    for series in form.iter().filter(|series| teams.contains(&series.team)) {
+        for point in &series.points {
+            let totals = &point.totals;
+            writer.write_record([
+                series.team.to_string(),
+                point.game_week.to_string(),
+                totals.shots_per_corner().to_string(),
+                totals.xg_per_shot().to_string(),
+                totals.shots_against_per_corner().to_string(),
+                totals.xg_against_per_shot().to_string(),
+            ])?;
+        }
+    }
+    writer.flush().with_path(&path)?;
+
+    Ok(())
+}
+
+fn corner_trend(input: &Path, window: NonZeroUsize, options: &Options) -> Result<()> {
+    let records = load_corners(input, options)?;
+    let size = options.size_or((1280, 720));
+
+    let charts = [
+        (
+            "corner_trend_xg",
+            "Corner xG",
+            "xG from Corner Kicks",
+            false,
+        ),
+        (
+            "corner_trend_xg_against",
+            "Corner xG Conceded",
+            "xG Conceded from Corner Kicks",
+            true,
+        ),
+    ];
+    for (name, title, y_desc, against) in charts {
+        let values = corners::per_match(&records, |d| if against { d.xg_against } else { d.xg });
+        let teams = options
+            .select_teams(values.iter().map(|(team, _)| *team).collect())
+            .with_path(input)?;
+
+        let mut chart = TrendChart::new(format!("{title}, {window}-Match Rolling Average"))
+            .y_desc(y_desc)
+            .rolling(window)
+            .league(values.iter().map(|(_, values)| values.clone()));
+        for (team, values) in values.iter().filter(|(team, _)| teams.contains(team)) {
+            chart = chart.team(*team, values.clone());
+        }
+        chart.save(options.output(name), size)?;
+        options.write_data(name, &chart.data()?)?;
+    }
+
+    Ok(())
+}
+
+fn corner_xg(input: &Path, options: &Options) -> Result<()> {
+    let records = load_corners(input, options)?;
+    let totals = corners::totals(&corners::match_frame(&records)?)?.sort_by_desc("xg")?;
+    let teams = options
+        .select_teams(totals.teams("team")?.to_vec())
+        .with_path(input)?;
+    let df = totals.filter(of_teams(&teams));
+
+    corners::xg_chart(&df)?.save(options.output("corner_xg"), options.size_or((1600, 900)))?;
+    options.write_data(
+        "corner_xg",
+        &df.select(&[
+            "team",
+            "matches",
+            "total_ck_for",
+            "xg",
+            "total_ck_against",
+            "xg_against",
+        ])?,
+    )
+}
+
+fn field_tilt(input: &Path, options: &Options) -> Result<()> {
+    let series = load_series(input, options)?;
+    let teams = options
+        .select_teams(series_teams(&series))
+        .with_path(input)?;
+    let aggregates = state_aggregates(&series, metrics::field_tilt)?;
+    let df = StateFrame::new(teams.clone(), &aggregates)?;
+
+    PanelReport::new(df.teams.clone())
+        .panel(MetricPanel::field_tilt(&df))
+        .save(options.output("field_tilt"), options.size_or((1280, 960)))?;
+    options.write_data("field_tilt", &aggregates.filter(of_teams(&teams)))
+}
+
+fn ppda(input: &Path, options: &Options) -> Result<()> {
+    let series = load_series(input, options)?;
+    let teams = options
+        .select_teams(series_teams(&series))
+        .with_path(input)?;
+    let aggregates = state_aggregates(&series, metrics::ppda)?;
+    let df = StateFrame::new(teams.clone(), &aggregates)?;
+
+    PanelReport::new(df.teams.clone())
+        .panel(MetricPanel::ppda(&df))
+        .save(options.output("ppda"), options.size_or((1024, 768)))?;
+    options.write_data("ppda", &aggregates.filter(of_teams(&teams)))
+}
+
+fn report(field_tilt_input: &Path, ppda_input: &Path, options: &Options) -> Result<()> {
+    let field_tilt_series = load_series(field_tilt_input, options)?;
+    let ppda_series = load_series(ppda_input, options)?;
+    let mut teams = series_teams(&field_tilt_series);
+    teams.extend(series_teams(&ppda_series));
+    teams.sort_by_key(|team| team.name());
+    teams.dedup();
+    let teams = options.select_teams(teams)?;
+
+    let field_tilt = state_aggregates(&field_tilt_series, metrics::field_tilt)?;
+    let ppda = state_aggregates(&ppda_series, metrics::ppda)?;
+
+    PanelReport::new(teams.clone())
+        .title("Field Tilt and PPDA per Gamestate")
+        .panel(MetricPanel::field_tilt(&StateFrame::new(
+            teams.clone(),
+            &field_tilt,
+        )?))
+        .panel(MetricPanel::ppda(&StateFrame::new(teams.clone(), &ppda)?))
+        .save(options.output("report"), options.size_or((1920, 1080)))?;
+
+    let with_metric = |df: DataFrame, metric: &str| {
+        let df = df.filter(of_teams(&teams));
+        let metric = vec![metric.to_string(); df.len()];
+        df.with_column("metric", metric)
+    };
+    let data = with_metric(field_tilt, "field_tilt")?.vstack(&with_metric(ppda, "ppda")?)?;
+    options.write_data("report", &data)
+}
+
+/// Writes the tables behind the charts, with the same game-week and team selection.
+#[cfg(feature = "arrow")]
+fn export(
+    corners_input: &Path,
+    field_tilt_input: &Path,
+    ppda_input: &Path,
+    format: TableFormat,
+    options: &Options,
+) -> Result<()> {
+    let write = |df: &DataFrame, name: &str| {
+        let teams = options.select_teams(df.teams("team")?.to_vec())?;
+        let path = options
+            .out_dir
+            .join(name)
+            .with_extension(format.extension());
+        export::write(&df.filter(of_teams(&teams)), path)
+    };
+
+    let matches = corners::match_frame(&load_corners(corners_input, options)?)?;
+    write(&matches, "corner_matches")?;
+    write(&corners::totals(&matches)?, "corner_totals")?;
+
+    let field_tilt = state_aggregates(
+        &load_series(field_tilt_input, options)?,
+        metrics::field_tilt,
+    )?;
+    write(&field_tilt, "field_tilt")?;
+    let ppda = state_aggregates(&load_series(ppda_input, options)?, metrics::ppda)?;
+    write(&ppda, "ppda")?;
+
+    Ok(())
+}
+
+/// Prints a report per file, failing when any has errors.
+fn validate(corners: &Path, field_tilt: &Path, ppda: &Path) -> Result<ExitCode> {
+    let reports = [
+        validate::validate_corners(corners)?,
+        validate::validate_game_state(field_tilt)?,
+        validate::validate_game_state(ppda)?,
+    ];
+
+    for report in &reports {
+        print!("{report}");
+    }
+    let errors = reports
+        .iter()
+        .map(|report| report.count(Severity::Error))
+        .sum::<usize>();
+    if errors > 0 {
+        eprintln!("{errors} error(s) found");
+        return Ok(ExitCode::FAILURE);
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("error: {err}");
+            let mut source = err.source();
+            while let Some(cause) = source {
+                eprintln!("  caused by: {cause}");
+                source = cause.source();
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<ExitCode> {
+    let options = &cli.options;
+
+    if let Some(path) = &options.registry {
+        TeamRegistry::from_file(path)?.install()?;
+    }
+    if let Some(dir) = &options.logo_dir {
+        LogoStore::new().dir(dir).install()?;
+    }
+    fs::create_dir_all(&options.out_dir).with_path(&options.out_dir)?;
+
+    let result = match &cli.command {
+        Command::Corners { input, last, half } => {
+            let window = match (last, half) {
+                (Some(n), _) => Some(Window::LastMatches(n.get())),
+                (_, Some(SeasonHalf::First)) => Some(Window::FirstHalf),
+                (_, Some(SeasonHalf::Second)) => Some(Window::SecondHalf),
+                (None, None) => None,
+            };
+            corners(input, window, options)
+        }
+        Command::CornerForm { input, window } => corner_form(input, *window, options),
+        Command::CornerTrend { input, window } => corner_trend(input, *window, options),
+        Command::CornerXg { input } => corner_xg(input, options),
+        Command::FieldTilt { input } => field_tilt(input, options),
+        Command::Ppda { input } => ppda(input, options),
+        Command::Report {
+            field_tilt_input,
+            ppda_input,
+        } => report(field_tilt_input, ppda_input, options),
+        #[cfg(feature = "arrow")]
+        Command::Export {
+            corners,
+            field_tilt,
+            ppda,
+            table_format,
+        } => export(corners, field_tilt, ppda, *table_format, options),
+        Command::Validate {
+            corners,
+            field_tilt,
+            ppda,
+        } => return validate(corners, field_tilt, ppda),
+    };
+
+    result.map(|()| ExitCode::SUCCESS)
+}
@@ -0,0 +1,227 @@
+use plotters::{
+    coord::Shift,
+    element::{PathElement, Text},
+    prelude::{DrawingArea, DrawingBackend},
+    style::{Color, TextStyle, BLACK},
+};
+
+use crate::Result;
+
+type BackendCoord = (i32, i32);
+
+const GAP: f64 = 4.;
+const MAX_ROUNDS: usize = 300;
+
+/// A label to place next to a marker drawn at `marker`, given as top-left and bottom-right
+/// pixel corners.
+#[derive(Debug, Clone)]
+pub struct MarkerLabel {
+    pub text: String,
+    pub marker: (BackendCoord, BackendCoord),
+}
+
+/// Where a label ended up, as the top-left pixel of its text.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Placement {
+    pub pos: BackendCoord,
+    /// Set when the label drifted far enough from its marker to need a leader line.
+    pub leader: Option<(BackendCoord, BackendCoord)>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Rect {
+    x0: f64,
+    y0: f64,
+    x1: f64,
+    y1: f64,
+}
+
+impl Rect {
+    fn new(pos: (f64, f64), size: (f64, f64)) -> Self {
+        Self {
+            x0: pos.0,
+            y0: pos.1,
+            x1: pos.0 + size.0,
+            y1: pos.1 + size.1,
+        }
+    }
+
+    fn center(&self) -> (f64, f64) {
+        ((self.x0 + self.x1) / 2., (self.y0 + self.y1) / 2.)
+    }
+
+    fn shift(&mut self, dx: f64, dy: f64) {
+        self.x0 += dx;
+        self.x1 += dx;
+        self.y0 += dy;
+        self.y1 += dy;
+    }
+
+    /// Smallest move of `self` that clears `other`, along one axis.
+    fn separation(&self, other: &Rect, tie_up: bool) -> Option<(f64, f64)> {
+        let overlap_x = self.x1.min(other.x1) - self.x0.max(other.x0);
+        let overlap_y = self.y1.min(other.y1) - self.y0.max(other.y0);
+        if overlap_x <= 0. || overlap_y <= 0. {
+            return None;
+        }
+
+        let (cx, cy) = self.center();
+        let (ox, oy) = other.center();
+        let sign = |ours: f64, theirs: f64| match ours.partial_cmp(&theirs) {
+            Some(std::cmp::Ordering::Less) => -1.,
+            Some(std::cmp::Ordering::Greater) => 1.,
+            _ if tie_up => -1.,
+            _ => 1.,
+        };
+        let (sx, sy) = (sign(cx, ox), sign(cy, oy));
+        let dx = if sx > 0. {
+            other.x1 - self.x0
+        } else {
+            self.x1 - other.x0
+        };
+        let dy = if sy > 0. {
+            other.y1 - self.y0
+        } else {
+            self.y1 - other.y0
+        };
+
+        // Labels are wide, so prefer sliding them up or down.
+        if dy <= dx * 2. {
+            Some((0., sy * (dy + 1.)))
+        } else {
+            Some((sx * (dx + 1.), 0.))
+        }
+    }
+
+    fn closest_point(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        (x.clamp(self.x0, self.x1), y.clamp(self.y0, self.y1))
+    }
+}
+
+/// Greedy repulsion: every label starts to the right of its marker and is pushed away from
+/// overlapping labels and markers until nothing overlaps or the rounds run out.
+///
+/// `sizes` are the text extents in pixels, `bounds` the pixel area labels must stay in.
+pub fn place_labels(
+    labels: &[MarkerLabel],
+    sizes: &[(u32, u32)],
+    bounds: (BackendCoord, BackendCoord),
+) -> Vec<Placement> {
+    let markers = labels
+        .iter()
+        .map(|label| {
+            let ((x0, y0), (x1, y1)) = label.marker;
+            Rect::new((x0 as f64, y0 as f64), ((x1 - x0) as f64, (y1 - y0) as f64))
+        })
+        .collect::<Vec<_>>();
+
+    let mut boxes = markers
+        .iter()
+        .zip(sizes)
+        .map(|(marker, (w, h))| {
+            let (_, cy) = marker.center();
+            Rect::new(
+                (marker.x1 + GAP, cy - *h as f64 / 2.),
+                (*w as f64, *h as f64),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let ((bx0, by0), (bx1, by1)) = bounds;
+    let clamp = |rect: &mut Rect| {
+        let dx = (bx0 as f64 - rect.x0).max(0.) - (rect.x1 - bx1 as f64).max(0.);
+        let dy = (by0 as f64 - rect.y0).max(0.) - (rect.y1 - by1 as f64).max(0.);
+        rect.shift(dx, dy);
+    };
+    boxes.iter_mut().for_each(clamp);
+    let start = boxes.clone();
+
+    for _ in 0..MAX_ROUNDS {
+        let mut moved = false;
+
+        for i in 0..boxes.len() {
+            let mut push = (0., 0.);
+            for (j, other) in boxes.iter().enumerate() {
+                if i == j {
+                    continue;
+                }
+                if let Some((dx, dy)) = boxes[i].separation(other, i < j) {
+                    // The other label takes the other half on its own turn.
+                    push.0 += dx / 2.;
+                    push.1 += dy / 2.;
+                }
+            }
+            for marker in &markers {
+                if let Some((dx, dy)) = boxes[i].separation(marker, false) {
+                    push.0 += dx;
+                    push.1 += dy;
+                }
+            }
+
+            if push != (0., 0.) {
+                boxes[i].shift(push.0, push.1);
+                clamp(&mut boxes[i]);
+                moved = true;
+            }
+        }
+
+        if !moved {
+            break;
+        }
+    }
+
+    boxes
+        .iter()
+        .zip(&start)
+        .zip(&markers)
+        .map(|((rect, start), marker)| {
+            let drift = (rect.x0 - start.x0).hypot(rect.y0 - start.y0);
+            let anchor = marker.center();
+            let leader = (drift > (marker.y1 - marker.y0).max(GAP) * 1.5).then(|| {
+                let end = rect.closest_point(anchor);
+                (
+                    (anchor.0 as i32, anchor.1 as i32),
+                    (end.0 as i32, end.1 as i32),
+                )
+            });
+            Placement {
+                pos: (rect.x0.round() as i32, rect.y0.round() as i32),
+                leader,
+            }
+        })
+        .collect()
+}
+
+/// Places and draws `labels` on `area`, with leader lines for labels that moved far.
+///
+/// Marker and bound coordinates are absolute backend pixels, as returned by
+/// `ChartContext::backend_coord`.
+pub fn draw_labels<DB: DrawingBackend<ErrorType: 'static>>(
+    area: &DrawingArea<DB, Shift>,
+    labels: &[MarkerLabel],
+    style: &TextStyle,
+    bounds: (BackendCoord, BackendCoord),
+) -> Result<()> {
+    let sizes = labels
+        .iter()
+        .map(|label| area.estimate_text_size(&label.text, style))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    let (base_x, base_y) = area.get_base_pixel();
+    let relative = |(x, y): BackendCoord| (x - base_x, y - base_y);
+
+    for (label, placement) in labels.iter().zip(place_labels(labels, &sizes, bounds)) {
+        if let Some((from, to)) = placement.leader {
+            area.draw(&PathElement::new(
+                vec![relative(from), relative(to)],
+                BLACK.mix(0.5),
+            ))?;
+        }
+        area.draw(&Text::new(
+            label.text.clone(),
+            relative(placement.pos),
+            style.clone(),
+        ))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use image::{imageops::FilterType, DynamicImage, RgbaImage};
+use plotters::style::RGBColor;
+
+/// Logo side in pixels on a chart of `REFERENCE_SIZE`, or at `REFERENCE_DPI`.
+const LOGO_SIZE: u32 = 15;
+const REFERENCE_SIZE: (u32, u32) = (1024, 768);
+const REFERENCE_DPI: u32 = 96;
+
+/// How big team logos are drawn.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogoSize {
+    /// Scales with the chart, 15 pixels on a 1024×768 one.
+    #[default]
+    Auto,
+    /// 15 pixels at 96 DPI, scaled to the given dots per inch.
+    Dpi(u32),
+    Pixels(u32),
+}
+
+impl LogoSize {
+    /// Side of the square a logo is fitted into on a chart of `size` pixels.
+    pub fn pixels(self, (width, height): (u32, u32)) -> u32 {
+        let scaled = |factor: f64| ((LOGO_SIZE as f64 * factor).round() as u32).max(1);
+        match self {
+            Self::Auto => scaled(
+                (width as f64 / REFERENCE_SIZE.0 as f64)
+                    .min(height as f64 / REFERENCE_SIZE.1 as f64),
+            ),
+            Self::Dpi(dpi) => scaled(dpi as f64 / REFERENCE_DPI as f64),
+            Self::Pixels(pixels) => pixels.max(1),
+        }
+    }
+}
+
+/// Resizes with Lanczos3 to fit a `side` pixel square, keeping the aspect ratio.
+pub(crate) fn fit_logo(logo: &DynamicImage, side: u32) -> RgbaImage {
+    logo.resize(side, side, FilterType::Lanczos3).into_rgba8()
+}
+
+/// Blends `logo` over `background(x, y)` for each of its pixels, into the RGB buffer
+/// plotters blits. Plotters has no alpha for bitmaps, so the chart underneath has to be
+/// known up front.
+pub(crate) fn composite<F>(logo: &RgbaImage, background: F) -> Vec<u8>
+where
+    F: Fn(u32, u32) -> RGBColor,
+{
+    logo.enumerate_pixels()
+        .flat_map(|(x, y, pixel)| {
+            let [r, g, b, a] = pixel.0;
+            let RGBColor(r, g, b) = blend(background(x, y), RGBColor(r, g, b), a as f64 / 255.);
+            [r, g, b]
+        })
+        .collect()
+}
+
+/// `color` drawn at `opacity` over `background`.
+pub(crate) fn blend(background: RGBColor, color: RGBColor, opacity: f64) -> RGBColor {
+    let mix = |bg: u8, fg: u8| (fg as f64 * opacity + bg as f64 * (1. - opacity)).round() as u8;
+    RGBColor(
+        mix(background.0, color.0),
+        mix(background.1, color.1),
+        mix(background.2, color.2),
+    )
+}
@@ -0,0 +1,15 @@
+pub mod labels;
+pub mod marker;
+pub mod output;
+pub mod palette;
+pub mod panel;
+pub mod scatter;
+pub mod trend;
+
+pub use labels::{draw_labels, place_labels, MarkerLabel, Placement};
+pub use marker::LogoSize;
+pub use output::{Chart, OutputFormat};
+pub use palette::{text_color, Palette};
+pub use panel::{BarLayout, BarSeries, MetricPanel, PanelReport};
+pub use scatter::{Crosshair, Quadrant, TeamScatter};
+pub use trend::{TrendChart, WeekValues};
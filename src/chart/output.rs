@@ -0,0 +1,91 @@
+use std::path::Path;
+use std::str::FromStr;
+
+use plotters::{
+    coord::Shift,
+    prelude::{BitMapBackend, DrawingArea, DrawingBackend, IntoDrawingArea, SVGBackend},
+};
+
+use crate::{MyError, Result, ResultExt};
+
+/// Image format of a rendered chart, picked from the output path's extension.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    #[default]
+    Png,
+    Svg,
+}
+
+impl OutputFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Svg => "svg",
+        }
+    }
+
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .ok_or_else(|| {
+                MyError::UnsupportedFormat(format!("No file extension on {}", path.display()))
+            })?
+            .parse()
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = MyError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "png" => Ok(Self::Png),
+            "svg" => Ok(Self::Svg),
+            other => Err(MyError::UnsupportedFormat(format!(
+                "Unsupported output format {other:?}, expected png or svg"
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.extension())
+    }
+}
+
+/// Anything that can draw itself onto a plotters drawing area.
+pub trait Chart {
+    /// Backend errors are kept as the source of [`MyError::Plotters`], hence `'static`.
+    fn draw<DB: DrawingBackend<ErrorType: 'static>>(
+        &self,
+        area: &DrawingArea<DB, Shift>,
+    ) -> Result<()>;
+
+    /// Renders to `path` as PNG or SVG, depending on its extension.
+    fn save<P: AsRef<Path>>(&self, path: P, size: (u32, u32)) -> Result<()> {
+        let path = path.as_ref();
+        match OutputFormat::from_path(path)? {
+            OutputFormat::Png => {
+                let root = BitMapBackend::new(path, size).into_drawing_area();
+                self.draw(&root)?;
+                root.present()?;
+            }
+            OutputFormat::Svg => {
+                let mut svg = String::new();
+                {
+                    let root = SVGBackend::with_string(&mut svg, size).into_drawing_area();
+                    self.draw(&root)?;
+                    root.present()?;
+                }
+                // plotters embeds bitmaps without the `image/` part of the media type,
+                // which browsers and editors refuse to display.
+                let svg = svg.replace("data:png;base64,", "data:image/png;base64,");
+                std::fs::write(path, svg).with_path(path)?;
+            }
+        }
+
+        Ok(())
+    }
+}
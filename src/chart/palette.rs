@@ -0,0 +1,99 @@
+use plotters::style::{RGBColor, BLACK, WHITE};
+
+use crate::{chart::marker::blend, Teams};
+
+/// Colours closer than this, by [`distance`], are hard to tell apart in a legend.
+const MIN_DISTANCE: f64 = 100.;
+
+/// Colours for the teams on one chart.
+///
+/// Every team gets its primary colour unless that clashes with a team earlier in the list,
+/// or with the white background. It then falls back to its secondary colour, and then to
+/// darker or lighter shades of both.
+#[derive(Debug, Clone, Default)]
+pub struct Palette {
+    colors: Vec<(Teams, RGBColor)>,
+}
+
+impl Palette {
+    pub fn new<I: IntoIterator<Item = Teams>>(teams: I) -> Self {
+        let mut colors: Vec<(Teams, RGBColor)> = Vec::new();
+        for team in teams {
+            if colors.iter().any(|(taken, _)| *taken == team) {
+                continue;
+            }
+            let info = team.info();
+            let candidates = [
+                info.primary,
+                info.secondary,
+                blend(info.primary, BLACK, 0.35),
+                blend(info.primary, WHITE, 0.35),
+                blend(info.secondary, BLACK, 0.35),
+                blend(info.primary, BLACK, 0.6),
+            ];
+            // How far a candidate is from everything it must stand out against.
+            let clearance = |color: RGBColor| {
+                colors
+                    .iter()
+                    .map(|(_, taken)| distance(color, *taken))
+                    .fold(distance(color, WHITE), f64::min)
+            };
+            let color = candidates
+                .iter()
+                .copied()
+                .find(|color| clearance(*color) >= MIN_DISTANCE)
+                .unwrap_or_else(|| {
+                    candidates
+                        .into_iter()
+                        .max_by(|a, b| clearance(*a).total_cmp(&clearance(*b)))
+                        .expect("candidates are not empty")
+                });
+            colors.push((team, color));
+        }
+
+        Self { colors }
+    }
+
+    /// The team's colour on this chart, its primary colour if it was not in the list.
+    pub fn color(&self, team: Teams) -> RGBColor {
+        self.colors
+            .iter()
+            .find(|(taken, _)| *taken == team)
+            .map_or_else(|| RGBColor::from(team), |(_, color)| *color)
+    }
+}
+
+/// Black or white, whichever reads better on `background` by WCAG contrast ratio.
+pub fn text_color(background: RGBColor) -> RGBColor {
+    let luminance = relative_luminance(background);
+    // Contrast with white is 1.05 / (L + 0.05), with black (L + 0.05) / 0.05.
+    if (luminance + 0.05).powi(2) > 1.05 * 0.05 {
+        BLACK
+    } else {
+        WHITE
+    }
+}
+
+/// WCAG relative luminance, from 0 for black to 1 for white.
+fn relative_luminance(RGBColor(r, g, b): RGBColor) -> f64 {
+    let linear = |channel: u8| {
+        let c = channel as f64 / 255.;
+        if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b)
+}
+
+/// The "redmean" approximation of perceived colour difference, 0 to about 765.
+fn distance(a: RGBColor, b: RGBColor) -> f64 {
+    let mean_r = (a.0 as f64 + b.0 as f64) / 2.;
+    let (dr, dg, db) = (
+        a.0 as f64 - b.0 as f64,
+        a.1 as f64 - b.1 as f64,
+        a.2 as f64 - b.2 as f64,
+    );
+    ((2. + mean_r / 256.) * dr * dr + 4. * dg * dg + (2. + (255. - mean_r) / 256.) * db * db).sqrt()
+}
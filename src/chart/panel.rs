@@ -0,0 +1,424 @@
+use std::collections::HashMap;
+
+use plotters::{
+    chart::{ChartBuilder, SeriesLabelPosition},
+    coord::Shift,
+    element::{DashedPathElement, PathElement, Rectangle, Text},
+    prelude::{DrawingArea, DrawingBackend},
+    style::{
+        full_palette::{GREEN, GREY, ORANGE, RED},
+        text_anchor::{HPos, Pos, VPos},
+        Color, IntoFont, RGBColor, TextStyle, BLACK, WHITE,
+    },
+};
+
+use crate::{
+    chart::{text_color, Chart, Palette},
+    game_state::{GameState, StateFrame},
+    Result, Teams,
+};
+
+const ROW_BAND: f64 = 0.8;
+const TEAM_LABEL_AREA: u32 = 170;
+const COLUMN_WIDTH: u32 = 110;
+
+#[derive(Debug, Clone)]
+pub struct BarSeries {
+    label: String,
+    color: RGBColor,
+    values: HashMap<Teams, f64>,
+    negative: bool,
+    by_team: bool,
+}
+
+impl BarSeries {
+    pub fn new(label: impl Into<String>, color: RGBColor, values: HashMap<Teams, f64>) -> Self {
+        Self {
+            label: label.into(),
+            color,
+            values,
+            negative: false,
+            by_team: false,
+        }
+    }
+
+    /// Every bar in its team's colour from the chart's [`Palette`], without a legend entry.
+    pub fn by_team(label: impl Into<String>, values: HashMap<Teams, f64>) -> Self {
+        Self {
+            by_team: true,
+            ..Self::new(label, GREY, values)
+        }
+    }
+
+    /// One game state of `frame`, in the green/orange/red used across the reports.
+    pub fn game_state(frame: &StateFrame, state: GameState) -> Self {
+        let color = match state {
+            GameState::Winning => GREEN,
+            GameState::Drawing => ORANGE,
+            GameState::Losing => RED,
+        };
+        Self::new(state.to_string(), color, frame.get(state).clone())
+    }
+
+    /// Draws the bars to the left of zero.
+    pub fn negative(mut self) -> Self {
+        self.negative = true;
+        self
+    }
+
+    fn signed(&self, team: &Teams) -> Option<f64> {
+        let value = *self.values.get(team)?;
+        Some(if self.negative { -value } else { value })
+    }
+
+    fn color(&self, team: Teams, palette: &Palette) -> RGBColor {
+        if self.by_team {
+            palette.color(team)
+        } else {
+            self.color
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BarLayout {
+    /// Every series gets its own slice of the team row.
+    Grouped,
+    /// Positive and negative series share the team row from opposite sides of zero.
+    Diverging,
+}
+
+/// One bar chart of per-team values, drawn as a panel of a [`PanelReport`].
+#[derive(Debug, Clone)]
+pub struct MetricPanel {
+    title: String,
+    x_desc: Option<String>,
+    layout: BarLayout,
+    series: Vec<BarSeries>,
+    reference: Option<f64>,
+    column: Option<(String, HashMap<Teams, f64>)>,
+    legend_position: SeriesLabelPosition,
+    bar_labels: bool,
+}
+
+impl MetricPanel {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            x_desc: None,
+            layout: BarLayout::Grouped,
+            series: Vec::new(),
+            reference: None,
+            column: None,
+            legend_position: SeriesLabelPosition::UpperRight,
+            bar_labels: false,
+        }
+    }
+
+    pub fn x_desc(mut self, desc: impl Into<String>) -> Self {
+        self.x_desc = Some(desc.into());
+        self
+    }
+
+    pub fn layout(mut self, layout: BarLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    pub fn series(mut self, series: BarSeries) -> Self {
+        self.series.push(series);
+        self
+    }
+
+    /// Dotted vertical line at `x`.
+    pub fn reference_line(mut self, x: f64) -> Self {
+        self.reference = Some(x);
+        self
+    }
+
+    /// Extra column of numbers printed next to each team row.
+    pub fn value_column(mut self, header: impl Into<String>, values: HashMap<Teams, f64>) -> Self {
+        self.column = Some((header.into(), values));
+        self
+    }
+
+    pub fn legend_position(mut self, position: SeriesLabelPosition) -> Self {
+        self.legend_position = position;
+        self
+    }
+
+    /// Prints every value at the end of its bar, inside it in black or white, whichever
+    /// reads better on the bar, when the text fits.
+    pub fn bar_labels(mut self) -> Self {
+        self.bar_labels = true;
+        self
+    }
+
+    /// Field tilt per game state, with the 0.5 line and the mean over states.
+    pub fn field_tilt(frame: &StateFrame) -> Self {
+        Self::new("Average Field Tilt per Gamestate")
+            .x_desc("Average Field Tilt")
+            .series(BarSeries::game_state(frame, GameState::Winning))
+            .series(BarSeries::game_state(frame, GameState::Drawing))
+            .series(BarSeries::game_state(frame, GameState::Losing))
+            .reference_line(0.5)
+            .value_column("Total", frame.state_mean())
+            .legend_position(SeriesLabelPosition::LowerRight)
+    }
+
+    /// PPDA per game state, with losing drawn to the left of zero.
+    pub fn ppda(frame: &StateFrame) -> Self {
+        Self::new("Average PPDA Comparison between Gamestate")
+            .x_desc("PPDA (Losing to the left)")
+            .layout(BarLayout::Diverging)
+            .series(BarSeries::game_state(frame, GameState::Winning))
+            .series(BarSeries::game_state(frame, GameState::Drawing))
+            .series(BarSeries::game_state(frame, GameState::Losing).negative())
+    }
+
+    fn x_range(&self) -> std::ops::Range<f64> {
+        let values = self
+            .series
+            .iter()
+            .flat_map(|series| series.values.keys().filter_map(|team| series.signed(team)))
+            .chain(self.reference);
+        let (min, max) = values.fold((0f64, 0f64), |(min, max), n| (min.min(n), max.max(n)));
+
+        min * 1.1..max * 1.1
+    }
+
+    /// Vertical offset from the row centre and bar height for every series.
+    fn slots(&self) -> Vec<(f64, f64)> {
+        let group = |series: &BarSeries| match self.layout {
+            BarLayout::Grouped => false,
+            BarLayout::Diverging => series.negative,
+        };
+
+        self.series
+            .iter()
+            .enumerate()
+            .map(|(idx, series)| {
+                let peers = self.series.iter().filter(|s| group(s) == group(series));
+                let position = self.series[..idx]
+                    .iter()
+                    .filter(|s| group(s) == group(series))
+                    .count();
+                let height = ROW_BAND / peers.count() as f64;
+                let offset = ROW_BAND / 2. - height * (position as f64 + 0.5);
+                (offset, height)
+            })
+            .collect()
+    }
+
+    fn draw<DB: DrawingBackend<ErrorType: 'static>>(
+        &self,
+        area: &DrawingArea<DB, Shift>,
+        teams: &[Teams],
+        label_area: u32,
+    ) -> Result<()> {
+        let (chart_area, column_area) = match self.column {
+            Some(_) => {
+                let width = area.dim_in_pixel().0;
+                let (chart_area, column_area) =
+                    area.split_horizontally(width.saturating_sub(COLUMN_WIDTH));
+                (chart_area, Some(column_area))
+            }
+            None => (area.clone(), None),
+        };
+
+        let team_count = teams.len();
+        let y_range = -0.5..team_count as f64 - 0.5;
+
+        // First team at the top.
+        let row = |idx: usize| (team_count - 1 - idx) as f64;
+        let team_label = |y: &f64| {
+            let idx = y.round();
+            if label_area == 0 || (y - idx).abs() > 1e-6 || idx < 0. || idx as usize >= team_count {
+                return String::new();
+            }
+            teams[team_count - 1 - idx as usize].to_string()
+        };
+        let value_label = |x: &f64| {
+            let label = format!("{:.3}", x.abs());
+            label
+                .trim_end_matches('0')
+                .trim_end_matches('.')
+                .to_string()
+        };
+
+        let mut bar_ctx = ChartBuilder::on(&chart_area)
+            .margin(20)
+            .x_label_area_size(50)
+            .y_label_area_size(label_area)
+            .caption(&self.title, ("sans-serif", 30))
+            .build_cartesian_2d(self.x_range(), y_range.clone())?;
+
+        let mut mesh = bar_ctx.configure_mesh();
+        mesh.disable_y_mesh()
+            .y_labels(team_count)
+            .y_label_formatter(&team_label)
+            .x_label_formatter(&value_label)
+            .axis_desc_style(("sans-serif", 20));
+        if let Some(x_desc) = &self.x_desc {
+            mesh.x_desc(x_desc);
+        }
+        mesh.draw()?;
+
+        let palette = Palette::new(teams.iter().copied());
+        for (series, (offset, height)) in self.series.iter().zip(self.slots()) {
+            let bars =
+                bar_ctx.draw_series(teams.iter().enumerate().filter_map(|(idx, team)| {
+                    let value = series.signed(team)?;
+                    let y = row(idx) + offset;
+                    Some(Rectangle::new(
+                        [(0., y - height / 2.), (value, y + height / 2.)],
+                        series.color(*team, &palette).filled(),
+                    ))
+                }))?;
+            if !series.by_team {
+                let color = series.color;
+                bars.label(&series.label).legend(move |(x, y)| {
+                    Rectangle::new([(x, y - 5), (x + 15, y + 5)], color.filled())
+                });
+            }
+        }
+
+        if self.bar_labels {
+            let (base_x, base_y) = chart_area.get_base_pixel();
+            let style = TextStyle::from(("sans-serif", 14).into_font());
+            for (series, (offset, _)) in self.series.iter().zip(self.slots()) {
+                for (idx, team) in teams.iter().enumerate() {
+                    let Some(value) = series.signed(team) else {
+                        continue;
+                    };
+                    let y = row(idx) + offset;
+                    let (zero, py) = bar_ctx.backend_coord(&(0., y));
+                    let (end, _) = bar_ctx.backend_coord(&(value, y));
+                    let text = value_label(&value);
+                    let (width, _) = chart_area.estimate_text_size(&text, &style)?;
+                    let inside = end.abs_diff(zero) > width + 8;
+                    // Inside the bar reads towards zero, outside away from it.
+                    let (x, hpos) = match (inside, value < 0.) {
+                        (true, false) | (false, true) => (end - 4, HPos::Right),
+                        (true, true) | (false, false) => (end + 4, HPos::Left),
+                    };
+                    let color = if inside {
+                        text_color(series.color(*team, &palette))
+                    } else {
+                        BLACK
+                    };
+                    chart_area.draw(&Text::new(
+                        text,
+                        (x - base_x, py - base_y),
+                        style
+                            .clone()
+                            .color(&color)
+                            .pos(Pos::new(hpos, VPos::Center)),
+                    ))?;
+                }
+            }
+        }
+
+        if self.series.iter().any(|series| series.negative) {
+            bar_ctx.draw_series(std::iter::once(PathElement::new(
+                [(0., y_range.start), (0., y_range.end)],
+                BLACK,
+            )))?;
+        }
+
+        if let Some(x) = self.reference {
+            bar_ctx.draw_series(std::iter::once(DashedPathElement::new(
+                [(x, y_range.start), (x, y_range.end)],
+                2,
+                4,
+                BLACK,
+            )))?;
+        }
+
+        if self.series.iter().any(|series| !series.by_team) {
+            bar_ctx
+                .configure_series_labels()
+                .position(self.legend_position.clone())
+                .background_style(WHITE)
+                .border_style(BLACK)
+                .draw()?;
+        }
+
+        if let (Some((header, values)), Some(column_area)) = (&self.column, column_area) {
+            // `backend_coord` is absolute, the column area draws relative to its corner.
+            let base_y = column_area.get_base_pixel().1;
+            let text_style = TextStyle::from(("sans-serif", 18).into_font()).color(&BLACK);
+            let header_y = bar_ctx.backend_coord(&(0., y_range.end)).1 - base_y - 20;
+            column_area.draw(&Text::new(
+                header.clone(),
+                (10, header_y),
+                text_style.clone(),
+            ))?;
+
+            for (idx, team) in teams.iter().enumerate() {
+                let Some(value) = values.get(team) else {
+                    continue;
+                };
+                let (_, y) = bar_ctx.backend_coord(&(0., row(idx)));
+                column_area.draw(&Text::new(
+                    format!("{value:.3}"),
+                    (10, y - base_y - 9),
+                    text_style.clone(),
+                ))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Several [`MetricPanel`]s side by side, sharing one team axis.
+#[derive(Debug, Clone)]
+pub struct PanelReport {
+    teams: Vec<Teams>,
+    title: Option<String>,
+    panels: Vec<MetricPanel>,
+}
+
+impl PanelReport {
+    /// `teams` fixes the row order, top to bottom, for every panel.
+    pub fn new(teams: Vec<Teams>) -> Self {
+        Self {
+            teams,
+            title: None,
+            panels: Vec::new(),
+        }
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn panel(mut self, panel: MetricPanel) -> Self {
+        self.panels.push(panel);
+        self
+    }
+}
+
+impl Chart for PanelReport {
+    fn draw<DB: DrawingBackend<ErrorType: 'static>>(
+        &self,
+        area: &DrawingArea<DB, Shift>,
+    ) -> Result<()> {
+        area.fill(&WHITE)?;
+        let area = match &self.title {
+            Some(title) => area.titled(title, ("sans-serif", 40))?,
+            None => area.clone(),
+        };
+
+        let panel_areas = area.split_evenly((1, self.panels.len().max(1)));
+        for (idx, (panel, panel_area)) in self.panels.iter().zip(&panel_areas).enumerate() {
+            // Only the first panel carries team names; the rest line up with it.
+            let label_area = if idx == 0 { TEAM_LABEL_AREA } else { 0 };
+            panel.draw(panel_area, &self.teams, label_area)?;
+        }
+
+        Ok(())
+    }
+}
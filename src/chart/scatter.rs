@@ -0,0 +1,421 @@
+use std::collections::HashMap;
+
+use plotters::{
+    chart::ChartBuilder,
+    coord::Shift,
+    element::{BitMapElement, Circle, DashedPathElement, Rectangle, Text},
+    prelude::{DrawingArea, DrawingBackend, LineSeries},
+    style::{
+        full_palette::{GREY_400, GREY_500, GREY_600},
+        text_anchor::{HPos, Pos, VPos},
+        Color, IntoFont, RGBColor, TextStyle, BLACK, WHITE,
+    },
+};
+
+use crate::{
+    chart::{
+        labels::{draw_labels, MarkerLabel},
+        marker::{blend, composite, fit_logo},
+        text_color, Chart, LogoSize, Palette,
+    },
+    Result, Teams,
+};
+
+const ISO_SAMPLES: usize = 100;
+
+/// Where the crosshair lines cross, per axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Crosshair {
+    Mean,
+    Median,
+}
+
+impl Crosshair {
+    fn center(self, mut values: Vec<f64>) -> f64 {
+        if values.is_empty() {
+            return 0.;
+        }
+        match self {
+            Self::Mean => values.iter().sum::<f64>() / values.len() as f64,
+            Self::Median => {
+                values.sort_by(f64::total_cmp);
+                let mid = values.len() / 2;
+                if values.len().is_multiple_of(2) {
+                    (values[mid - 1] + values[mid]) / 2.
+                } else {
+                    values[mid]
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quadrant {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// Scatter plot of one point per team, drawn with club logos and non-overlapping labels.
+#[derive(Debug, Clone)]
+pub struct TeamScatter {
+    title: String,
+    x_desc: String,
+    y_desc: String,
+    points: Vec<(Teams, f64, f64)>,
+    margin: u32,
+    crosshair: Option<Crosshair>,
+    shading: Vec<(Quadrant, RGBColor)>,
+    corner_text: Vec<(Quadrant, String)>,
+    iso_lines: Option<(String, Vec<f64>)>,
+    logo_size: LogoSize,
+}
+
+impl TeamScatter {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            x_desc: String::new(),
+            y_desc: String::new(),
+            points: Vec::new(),
+            margin: 10,
+            crosshair: None,
+            shading: Vec::new(),
+            corner_text: Vec::new(),
+            iso_lines: None,
+            logo_size: LogoSize::default(),
+        }
+    }
+
+    pub fn x_desc(mut self, desc: impl Into<String>) -> Self {
+        self.x_desc = desc.into();
+        self
+    }
+
+    pub fn y_desc(mut self, desc: impl Into<String>) -> Self {
+        self.y_desc = desc.into();
+        self
+    }
+
+    pub fn points<I: IntoIterator<Item = (Teams, f64, f64)>>(mut self, points: I) -> Self {
+        self.points.extend(points);
+        self
+    }
+
+    pub fn margin(mut self, margin: u32) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Dashed lines through the league mean or median of both axes.
+    pub fn crosshair(mut self, crosshair: Crosshair) -> Self {
+        self.crosshair = Some(crosshair);
+        self
+    }
+
+    /// Tints one quadrant around the crosshair, or around the mean without one.
+    pub fn shade_quadrant(mut self, quadrant: Quadrant, color: RGBColor) -> Self {
+        self.shading.push((quadrant, color));
+        self
+    }
+
+    /// Text in one corner of the plotting area.
+    pub fn corner_text(mut self, quadrant: Quadrant, text: impl Into<String>) -> Self {
+        self.corner_text.push((quadrant, text.into()));
+        self
+    }
+
+    /// Curves of constant `x * y`, one per level, labelled as `"{level} {desc}"`.
+    pub fn iso_lines<I: IntoIterator<Item = f64>>(
+        mut self,
+        desc: impl Into<String>,
+        levels: I,
+    ) -> Self {
+        self.iso_lines = Some((desc.into(), levels.into_iter().collect()));
+        self
+    }
+
+    pub fn logo_size(mut self, size: LogoSize) -> Self {
+        self.logo_size = size;
+        self
+    }
+
+    /// Axis range from the smallest to the largest value, padded by 10% of the span on
+    /// both ends. A single value gets 10% of itself, and at least 0.05, either side.
+    fn padded_range(values: impl Iterator<Item = f64>) -> std::ops::Range<f64> {
+        let (min, max) = values
+            .filter(|value| value.is_finite())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| {
+                (min.min(value), max.max(value))
+            });
+        if min > max {
+            return 0.0..1.0;
+        }
+
+        let pad = if max > min {
+            (max - min) * 0.1
+        } else {
+            (max.abs() * 0.1).max(0.05)
+        };
+        min - pad..max + pad
+    }
+}
+
+impl Chart for TeamScatter {
+    fn draw<DB: DrawingBackend<ErrorType: 'static>>(
+        &self,
+        root: &DrawingArea<DB, Shift>,
+    ) -> Result<()> {
+        let side = self.logo_size.pixels(root.dim_in_pixel());
+        let logos = self
+            .points
+            .iter()
+            .map(|(team, _, _)| Ok(team.logo()?.map(|logo| fit_logo(&logo, side))))
+            .collect::<Result<Vec<_>>>()?;
+
+        let x_range = Self::padded_range(self.points.iter().map(|(_, x, _)| *x));
+        let y_range = Self::padded_range(self.points.iter().map(|(_, _, y)| *y));
+
+        root.fill(&WHITE)?;
+
+        let mut scatter_ctx = ChartBuilder::on(root)
+            .margin(self.margin)
+            .x_label_area_size(50)
+            .y_label_area_size(55)
+            .caption(&self.title, ("sans-serif", 35))
+            .build_cartesian_2d(x_range.clone(), y_range.clone())?;
+
+        let center = |crosshair: Crosshair| {
+            (
+                crosshair.center(self.points.iter().map(|(_, x, _)| *x).collect()),
+                crosshair.center(self.points.iter().map(|(_, _, y)| *y).collect()),
+            )
+        };
+        let (x_center, y_center) = center(self.crosshair.unwrap_or(Crosshair::Mean));
+
+        scatter_ctx.draw_series(self.shading.iter().map(|(quadrant, color)| {
+            let (x, y) = match quadrant {
+                Quadrant::TopLeft => (x_range.start, y_range.end),
+                Quadrant::TopRight => (x_range.end, y_range.end),
+                Quadrant::BottomLeft => (x_range.start, y_range.start),
+                Quadrant::BottomRight => (x_range.end, y_range.start),
+            };
+            Rectangle::new([(x_center, y_center), (x, y)], color.mix(0.1).filled())
+        }))?;
+
+        let (x_pixels, y_pixels) = scatter_ctx.plotting_area().get_pixel_range();
+        let (base_x, base_y) = root.get_base_pixel();
+        let markers = self
+            .points
+            .iter()
+            .map(|(_, x, y)| {
+                let (px, py) = scatter_ctx.backend_coord(&(*x, *y));
+                (px - side as i32 / 2, py - side as i32 / 2)
+            })
+            .collect::<Vec<_>>();
+
+        // Plotters blits logos without alpha, so their transparent parts are blended here
+        // with what is under them: an earlier marker, else the quadrant shading. Axes,
+        // iso-lines and the crosshair are drawn afterwards.
+        let (cx, cy) = scatter_ctx.backend_coord(&(x_center, y_center));
+        let shading = |x: i32, y: i32| {
+            if !x_pixels.contains(&x) || !y_pixels.contains(&y) {
+                return WHITE;
+            }
+            let quadrant = match (x < cx, y < cy) {
+                (true, true) => Quadrant::TopLeft,
+                (false, true) => Quadrant::TopRight,
+                (true, false) => Quadrant::BottomLeft,
+                (false, false) => Quadrant::BottomRight,
+            };
+            self.shading
+                .iter()
+                .filter(|(q, _)| *q == quadrant)
+                .fold(WHITE, |bg, (_, color)| blend(bg, *color, 0.1))
+        };
+        let mut drawn: HashMap<(i32, i32), RGBColor> = HashMap::new();
+
+        let radius = side as i32 / 2;
+        let code_style = TextStyle::from(("sans-serif", side as f64 * 0.45).into_font())
+            .pos(Pos::new(HPos::Center, VPos::Center));
+        let palette = Palette::new(self.points.iter().map(|(team, _, _)| *team));
+        for (((team, _, _), logo), (mx, my)) in self.points.iter().zip(&logos).zip(&markers) {
+            match logo {
+                Some(logo) => {
+                    // Centred in the marker square, as the logo keeps its aspect ratio.
+                    let x = mx + (side - logo.width()) as i32 / 2;
+                    let y = my + (side - logo.height()) as i32 / 2;
+                    let buffer = composite(logo, |lx, ly| {
+                        let pixel = (x + lx as i32, y + ly as i32);
+                        drawn
+                            .get(&pixel)
+                            .copied()
+                            .unwrap_or_else(|| shading(pixel.0, pixel.1))
+                    });
+                    for (idx, rgb) in buffer.chunks(3).enumerate() {
+                        let (lx, ly) = (idx as u32 % logo.width(), idx as u32 / logo.width());
+                        drawn.insert(
+                            (x + lx as i32, y + ly as i32),
+                            RGBColor(rgb[0], rgb[1], rgb[2]),
+                        );
+                    }
+                    root.draw(
+                        &BitMapElement::with_owned_buffer(
+                            (x - base_x, y - base_y),
+                            logo.dimensions(),
+                            buffer,
+                        )
+                        .expect("buffer matches the logo dimensions"),
+                    )?;
+                }
+                // A circle in the team's colour with the short code stands in for a
+                // missing logo.
+                None => {
+                    let color = palette.color(*team);
+                    for dy in 0..side as i32 {
+                        for dx in 0..side as i32 {
+                            if (dx - radius).pow(2) + (dy - radius).pow(2) <= radius.pow(2) {
+                                drawn.insert((mx + dx, my + dy), color);
+                            }
+                        }
+                    }
+                    let center = (mx + radius - base_x, my + radius - base_y);
+                    root.draw(&Circle::new(center, radius, color.filled()))?;
+                    root.draw(&Text::new(
+                        team.short_code(),
+                        center,
+                        code_style.clone().color(&text_color(color)),
+                    ))?;
+                }
+            }
+        }
+
+        scatter_ctx
+            .configure_mesh()
+            .disable_x_mesh()
+            .disable_y_mesh()
+            .x_desc(&self.x_desc)
+            .y_desc(&self.y_desc)
+            .axis_desc_style(("sans-serif", 20))
+            .draw()?;
+
+        if let Some((desc, levels)) = &self.iso_lines {
+            let style = TextStyle::from(("sans-serif", 13).into_font()).color(&GREY_500);
+            for level in levels {
+                // y = level / x, clipped to the plotting area.
+                let x_from = x_range.start.max(level / y_range.end).max(f64::EPSILON);
+                let x_to = x_range.end.min(level / y_range.start.max(f64::EPSILON));
+                if x_from >= x_to {
+                    continue;
+                }
+                let curve = (0..=ISO_SAMPLES)
+                    .map(|idx| {
+                        let x = x_from + (x_to - x_from) * idx as f64 / ISO_SAMPLES as f64;
+                        (x, level / x)
+                    })
+                    .collect::<Vec<_>>();
+                let end = *curve.last().expect("curve has samples");
+                scatter_ctx.draw_series(LineSeries::new(curve, GREY_400))?;
+                scatter_ctx.draw_series(std::iter::once(Text::new(
+                    format!("{level} {desc}"),
+                    end,
+                    style.clone().pos(Pos::new(HPos::Right, VPos::Bottom)),
+                )))?;
+            }
+        }
+
+        if self.crosshair.is_some() {
+            let style = GREY_600.stroke_width(1);
+            scatter_ctx.draw_series([
+                DashedPathElement::new(
+                    [(x_center, y_range.start), (x_center, y_range.end)],
+                    4,
+                    4,
+                    style,
+                ),
+                DashedPathElement::new(
+                    [(x_range.start, y_center), (x_range.end, y_center)],
+                    4,
+                    4,
+                    style,
+                ),
+            ])?;
+        }
+
+        let style = TextStyle::from(("sans-serif", 16).into_font()).color(&GREY_600);
+        let inset = 8;
+        for (quadrant, text) in &self.corner_text {
+            let (x, y, pos) = match quadrant {
+                Quadrant::TopLeft => (
+                    x_pixels.start + inset,
+                    y_pixels.start + inset,
+                    (HPos::Left, VPos::Top),
+                ),
+                Quadrant::TopRight => (
+                    x_pixels.end - inset,
+                    y_pixels.start + inset,
+                    (HPos::Right, VPos::Top),
+                ),
+                Quadrant::BottomLeft => (
+                    x_pixels.start + inset,
+                    y_pixels.end - inset,
+                    (HPos::Left, VPos::Bottom),
+                ),
+                Quadrant::BottomRight => (
+                    x_pixels.end - inset,
+                    y_pixels.end - inset,
+                    (HPos::Right, VPos::Bottom),
+                ),
+            };
+            root.draw(&Text::new(
+                text.clone(),
+                (x - base_x, y - base_y),
+                style.clone().pos(Pos::new(pos.0, pos.1)),
+            ))?;
+        }
+
+        let labels = self
+            .points
+            .iter()
+            .zip(&markers)
+            .map(|((team, _, _), (mx, my))| MarkerLabel {
+                text: team.to_string(),
+                marker: ((*mx, *my), (mx + side as i32, my + side as i32)),
+            })
+            .collect::<Vec<_>>();
+        draw_labels(
+            root,
+            &labels,
+            &TextStyle::from(("sans-serif", 15).into_font()).color(&BLACK),
+            (
+                (x_pixels.start, y_pixels.start),
+                (x_pixels.end, y_pixels.end),
+            ),
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pads_by_the_span() {
+        let range = TeamScatter::padded_range([0.1, 0.3, 0.2].into_iter());
+        assert!((range.start - 0.08).abs() < 1e-12 && (range.end - 0.32).abs() < 1e-12);
+
+        let range = TeamScatter::padded_range([-2., 2.].into_iter());
+        assert_eq!(range, -2.4..2.4);
+    }
+
+    #[test]
+    fn pads_a_single_value() {
+        assert_eq!(TeamScatter::padded_range([0.].into_iter()), -0.05..0.05);
+        assert_eq!(TeamScatter::padded_range([-2., -2.].into_iter()), -2.2..-1.8);
+        assert_eq!(TeamScatter::padded_range(std::iter::empty()), 0.0..1.0);
+    }
+}
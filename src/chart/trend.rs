@@ -0,0 +1,245 @@
+use std::{collections::BTreeMap, num::NonZeroUsize};
+
+use plotters::{
+    chart::{ChartBuilder, SeriesLabelPosition},
+    coord::Shift,
+    element::{Circle, DashedPathElement, PathElement, Polygon},
+    prelude::{DrawingArea, DrawingBackend, LineSeries},
+    style::{full_palette::GREY_600, Color, BLACK, WHITE},
+};
+
+use crate::{
+    chart::{Chart, Palette},
+    DataFrame, Result, Teams,
+};
+
+/// One value per game week.
+pub type WeekValues = Vec<(u32, f64)>;
+
+/// Line chart of per-game-week values for a few teams, over a shaded band of where the
+/// rest of the league sits.
+#[derive(Debug, Clone)]
+pub struct TrendChart {
+    title: String,
+    x_desc: String,
+    y_desc: String,
+    teams: Vec<(Teams, WeekValues)>,
+    league: Vec<WeekValues>,
+    rolling: usize,
+}
+
+impl TrendChart {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            x_desc: "Game Week".to_string(),
+            y_desc: String::new(),
+            teams: Vec::new(),
+            league: Vec::new(),
+            rolling: 1,
+        }
+    }
+
+    pub fn x_desc(mut self, desc: impl Into<String>) -> Self {
+        self.x_desc = desc.into();
+        self
+    }
+
+    pub fn y_desc(mut self, desc: impl Into<String>) -> Self {
+        self.y_desc = desc.into();
+        self
+    }
+
+    /// A highlighted line in the team's colour, or another shade of it when an earlier
+    /// team's line looks too alike.
+    pub fn team(mut self, team: Teams, values: WeekValues) -> Self {
+        self.teams.push((team, values));
+        self
+    }
+
+    /// Every team's values, used for the league median and the band of the middle half
+    /// of the league around it.
+    pub fn league<I: IntoIterator<Item = WeekValues>>(mut self, series: I) -> Self {
+        self.league.extend(series);
+        self
+    }
+
+    /// Averages every line over the last `matches` values. The first few points
+    /// average over what is there so far.
+    pub fn rolling(mut self, matches: NonZeroUsize) -> Self {
+        self.rolling = matches.get();
+        self
+    }
+
+    /// The lines as drawn, one row per point: `series` (the team, or the league's
+    /// `lower quartile`, `median` and `upper quartile`), `game_week` and `value`.
+    pub fn data(&self) -> Result<DataFrame> {
+        let mut series = Vec::new();
+        let mut weeks = Vec::new();
+        let mut values = Vec::new();
+        let mut push = |name: &str, week: u32, value: f64| {
+            series.push(name.to_string());
+            weeks.push(week as i64);
+            values.push(value);
+        };
+
+        for (team, team_values) in &self.teams {
+            for (week, value) in self.smooth(team_values) {
+                push(team.name(), week, value);
+            }
+        }
+        for (week, lower, median, upper) in self.league_band() {
+            push("lower quartile", week, lower);
+            push("median", week, median);
+            push("upper quartile", week, upper);
+        }
+
+        DataFrame::new()
+            .with_column("series", series)?
+            .with_column("game_week", weeks)?
+            .with_column("value", values)
+    }
+
+    fn smooth(&self, values: &WeekValues) -> WeekValues {
+        let mut values = values.clone();
+        values.sort_by_key(|(week, _)| *week);
+
+        (0..values.len())
+            .map(|idx| {
+                let window = &values[(idx + 1).saturating_sub(self.rolling)..=idx];
+                let mean = window.iter().map(|(_, v)| v).sum::<f64>() / window.len() as f64;
+                (values[idx].0, mean)
+            })
+            .collect()
+    }
+
+    /// League lower quartile, median and upper quartile per game week, over the smoothed
+    /// values.
+    fn league_band(&self) -> Vec<(u32, f64, f64, f64)> {
+        let mut weeks: BTreeMap<u32, Vec<f64>> = BTreeMap::new();
+        for values in &self.league {
+            for (week, value) in self.smooth(values) {
+                weeks.entry(week).or_default().push(value);
+            }
+        }
+
+        weeks
+            .into_iter()
+            .map(|(week, mut values)| {
+                values.sort_by(f64::total_cmp);
+                (
+                    week,
+                    quantile(&values, 0.25),
+                    quantile(&values, 0.5),
+                    quantile(&values, 0.75),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Linear interpolation between the closest ranks of sorted, non-empty `values`.
+fn quantile(values: &[f64], q: f64) -> f64 {
+    let pos = q * (values.len() - 1) as f64;
+    let (lo, hi) = (pos.floor() as usize, pos.ceil() as usize);
+    values[lo] + (values[hi] - values[lo]) * (pos - lo as f64)
+}
+
+impl Chart for TrendChart {
+    fn draw<DB: DrawingBackend<ErrorType: 'static>>(
+        &self,
+        root: &DrawingArea<DB, Shift>,
+    ) -> Result<()> {
+        let band = self.league_band();
+        let lines = self
+            .teams
+            .iter()
+            .map(|(team, values)| (*team, values, self.smooth(values)))
+            .collect::<Vec<_>>();
+
+        let weeks = lines
+            .iter()
+            .flat_map(|(_, values, _)| values.iter().map(|(week, _)| *week))
+            .chain(band.iter().map(|(week, ..)| *week));
+        let (first, last) = weeks.fold((u32::MAX, 0), |(lo, hi), w| (lo.min(w), hi.max(w)));
+        let (first, last) = if first > last {
+            (0, 1)
+        } else {
+            (first, last.max(first + 1))
+        };
+
+        let values = lines
+            .iter()
+            .flat_map(|(_, raw, smooth)| raw.iter().chain(smooth).map(|(_, v)| *v))
+            .chain(
+                band.iter()
+                    .flat_map(|(_, lower, _, upper)| [*lower, *upper]),
+            );
+        let (min, max) = values.fold((0f64, 0f64), |(lo, hi), v| (lo.min(v), hi.max(v)));
+
+        root.fill(&WHITE)?;
+
+        let mut trend_ctx = ChartBuilder::on(root)
+            .margin(20)
+            .x_label_area_size(50)
+            .y_label_area_size(60)
+            .caption(&self.title, ("sans-serif", 35))
+            .build_cartesian_2d(first..last, min..max * 1.1)?;
+
+        trend_ctx
+            .configure_mesh()
+            .disable_x_mesh()
+            .light_line_style(WHITE)
+            .x_labels((last - first + 1) as usize)
+            .x_desc(&self.x_desc)
+            .y_desc(&self.y_desc)
+            .axis_desc_style(("sans-serif", 20))
+            .draw()?;
+
+        if !band.is_empty() {
+            let outline = band
+                .iter()
+                .map(|(week, _, _, upper)| (*week, *upper))
+                .chain(band.iter().rev().map(|(week, lower, ..)| (*week, *lower)))
+                .collect::<Vec<_>>();
+            trend_ctx.draw_series(std::iter::once(Polygon::new(
+                outline,
+                GREY_600.mix(0.15).filled(),
+            )))?;
+            trend_ctx
+                .draw_series(std::iter::once(DashedPathElement::new(
+                    band.iter().map(|(week, _, median, _)| (*week, *median)),
+                    4,
+                    4,
+                    GREY_600.stroke_width(2),
+                )))?
+                .label("League median, middle 50% shaded")
+                .legend(|(x, y)| PathElement::new([(x, y), (x + 20, y)], GREY_600.stroke_width(2)));
+        }
+
+        let palette = Palette::new(self.teams.iter().map(|(team, _)| *team));
+        for (team, raw, smooth) in &lines {
+            let color = palette.color(*team);
+            // Single matches stay visible as faint dots behind the averaged line.
+            trend_ctx.draw_series(
+                raw.iter()
+                    .map(|point| Circle::new(*point, 3, color.mix(0.35).filled())),
+            )?;
+            trend_ctx
+                .draw_series(LineSeries::new(smooth.clone(), color.stroke_width(3)))?
+                .label(team.to_string())
+                .legend(move |(x, y)| {
+                    PathElement::new([(x, y), (x + 20, y)], color.stroke_width(3))
+                });
+        }
+
+        trend_ctx
+            .configure_series_labels()
+            .position(SeriesLabelPosition::UpperRight)
+            .background_style(WHITE.mix(0.9))
+            .border_style(BLACK)
+            .draw()?;
+
+        Ok(())
+    }
+}
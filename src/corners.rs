@@ -0,0 +1,461 @@
+use std::{
+    collections::{HashMap, HashSet},
+    num::NonZeroUsize,
+    path::Path,
+};
+
+use plotters::style::full_palette::{GREEN, RED};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    chart::{BarSeries, Crosshair, MetricPanel, PanelReport, Quadrant, TeamScatter, WeekValues},
+    error::line_of,
+    game_state::Ratio,
+    store::{MatchPair, MatchRow, MatchStore},
+    Agg, DataFrame, Fixture, GameWeekRange, MyError, Result, ResultExt, Teams, Venue,
+};
+
+const ISO_XG_PER_CORNER: [f64; 4] = [0.01, 0.02, 0.03, 0.04];
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DataSource {
+    /// Line in the CSV file, for error messages.
+    #[serde(skip)]
+    pub row: u64,
+    pub team: Teams,
+    pub game: Fixture,
+    pub game_week: u32,
+    pub total_ck_for: u32,
+    pub shots_from_ck: u32,
+    pub xg: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CornerKickData {
+    pub team: Teams,
+    pub fixture: Fixture,
+    pub game_week: u32,
+    pub venue: Venue,
+    pub total_ck_for: u32,
+    pub total_ck_against: u32,
+    pub shots_from_ck: u32,
+    pub shots_against_from_ck: u32,
+    pub xg: f64,
+    pub xg_against: f64,
+}
+
+/// Reads a corner export and joins every row with the opponent's row of the same match,
+/// grouped by the game weeks present in the file.
+pub fn parse_csv<P: AsRef<Path>>(path: P) -> Result<HashMap<u32, Vec<CornerKickData>>> {
+    let path = path.as_ref();
+    read_csv(path).with_path(path)
+}
+
+fn read_csv(path: &Path) -> Result<HashMap<u32, Vec<CornerKickData>>> {
+    let mut csv_reader = csv::Reader::from_path(path)?;
+    let headers = csv_reader.headers()?.clone();
+
+    let mut data_sources = Vec::new();
+    for record in csv_reader.records() {
+        let record = record?;
+        let mut data_source: DataSource = record.deserialize(Some(&headers))?;
+        data_source.row = record.position().map_or(0, line_of);
+        data_sources.push(data_source);
+    }
+
+    if data_sources.is_empty() {
+        return Err(MyError::EmptyDataset { path: None });
+    }
+
+    join(data_sources)
+}
+
+impl MatchRow for DataSource {
+    fn team(&self) -> Teams {
+        self.team
+    }
+
+    fn fixture(&self) -> &Fixture {
+        &self.game
+    }
+
+    fn row(&self) -> u64 {
+        self.row
+    }
+}
+
+/// Joins every row with the opponent's row of the same match, in one pass over an index
+/// of the rows, and groups the results by game week.
+pub fn join(mut data_sources: Vec<DataSource>) -> Result<HashMap<u32, Vec<CornerKickData>>> {
+    // The same two teams meet twice a season, so the game week tells the fixtures apart.
+    for ds in &mut data_sources {
+        ds.game.game_week = Some(ds.game_week);
+    }
+    let store = MatchStore::new(data_sources);
+
+    let mut weeks: HashMap<u32, Vec<CornerKickData>> = HashMap::new();
+    for pair in store.paired() {
+        let MatchPair {
+            ours,
+            theirs,
+            venue,
+            ..
+        } = pair?;
+        weeks
+            .entry(ours.game_week)
+            .or_default()
+            .push(CornerKickData {
+                team: ours.team,
+                fixture: ours.game.clone(),
+                game_week: ours.game_week,
+                venue,
+                total_ck_for: ours.total_ck_for,
+                total_ck_against: theirs.total_ck_for,
+                shots_from_ck: ours.shots_from_ck,
+                shots_against_from_ck: theirs.shots_from_ck,
+                xg: ours.xg,
+                xg_against: theirs.xg,
+            });
+    }
+
+    Ok(weeks)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TeamCKData {
+    pub team_name: Teams,
+    pub total_ck_for: u32,
+    pub total_ck_against: u32,
+    pub shots_from_ck: u32,
+    pub shots_against_from_ck: u32,
+    pub xg: f64,
+    pub xg_against: f64,
+}
+
+impl TeamCKData {
+    fn sum<'a, I: IntoIterator<Item = &'a CornerKickData>>(team_name: Teams, data: I) -> Self {
+        let mut total = Self {
+            team_name,
+            total_ck_for: 0,
+            total_ck_against: 0,
+            shots_from_ck: 0,
+            shots_against_from_ck: 0,
+            xg: 0.,
+            xg_against: 0.,
+        };
+        for d in data {
+            total.total_ck_for += d.total_ck_for;
+            total.total_ck_against += d.total_ck_against;
+            total.shots_from_ck += d.shots_from_ck;
+            total.shots_against_from_ck += d.shots_against_from_ck;
+            total.xg += d.xg;
+            total.xg_against += d.xg_against;
+        }
+        total
+    }
+
+    pub fn shots_per_corner(&self) -> f64 {
+        Ratio::new(self.shots_from_ck as f64, self.total_ck_for as f64).value()
+    }
+
+    pub fn xg_per_shot(&self) -> f64 {
+        Ratio::new(self.xg, self.shots_from_ck as f64).value()
+    }
+
+    pub fn shots_against_per_corner(&self) -> f64 {
+        Ratio::new(
+            self.shots_against_from_ck as f64,
+            self.total_ck_against as f64,
+        )
+        .value()
+    }
+
+    pub fn xg_against_per_shot(&self) -> f64 {
+        Ratio::new(self.xg_against, self.shots_against_from_ck as f64).value()
+    }
+}
+
+/// Every team's matches sorted by game week, in one pass. Teams are in order of first
+/// appearance, so filtered-out weeks do not reorder anyone.
+fn by_team(input: &HashMap<u32, Vec<CornerKickData>>) -> Vec<(Teams, Vec<&CornerKickData>)> {
+    let mut game_weeks = input.keys().copied().collect::<Vec<_>>();
+    game_weeks.sort_unstable();
+
+    let mut positions: HashMap<Teams, usize> = HashMap::new();
+    let mut teams: Vec<(Teams, Vec<&CornerKickData>)> = Vec::new();
+    for d in game_weeks.iter().flat_map(|gw| &input[gw]) {
+        let idx = *positions.entry(d.team).or_insert_with(|| {
+            teams.push((d.team, Vec::new()));
+            teams.len() - 1
+        });
+        teams[idx].1.push(d);
+    }
+    teams
+}
+
+/// One row per team and match, with `team`, `opponent`, `game_week`, `venue` (`home` or
+/// `away`) and the corner counts and xG for and against. Rows are grouped by team, each
+/// team's matches in game-week order.
+pub fn match_frame(input: &HashMap<u32, Vec<CornerKickData>>) -> Result<DataFrame> {
+    let matches = by_team(input)
+        .into_iter()
+        .flat_map(|(_, matches)| matches)
+        .collect::<Vec<_>>();
+    let numbers =
+        |value: fn(&CornerKickData) -> f64| matches.iter().map(|d| value(d)).collect::<Vec<_>>();
+    let counts = |value: fn(&CornerKickData) -> u32| {
+        matches.iter().map(|d| value(d) as i64).collect::<Vec<_>>()
+    };
+
+    DataFrame::new()
+        .with_column("team", matches.iter().map(|d| d.team).collect::<Vec<_>>())?
+        .with_column(
+            "opponent",
+            matches
+                .iter()
+                .map(|d| match d.venue {
+                    Venue::Home => d.fixture.away,
+                    Venue::Away => d.fixture.home,
+                })
+                .collect::<Vec<_>>(),
+        )?
+        .with_column("game_week", counts(|d| d.game_week))?
+        .with_column(
+            "venue",
+            matches
+                .iter()
+                .map(|d| match d.venue {
+                    Venue::Home => "home".to_string(),
+                    Venue::Away => "away".to_string(),
+                })
+                .collect::<Vec<_>>(),
+        )?
+        .with_column("total_ck_for", counts(|d| d.total_ck_for))?
+        .with_column("total_ck_against", counts(|d| d.total_ck_against))?
+        .with_column("shots_from_ck", counts(|d| d.shots_from_ck))?
+        .with_column("shots_against_from_ck", counts(|d| d.shots_against_from_ck))?
+        .with_column("xg", numbers(|d| d.xg))?
+        .with_column("xg_against", numbers(|d| d.xg_against))
+}
+
+/// Per-team sums of a [`match_frame`], with `matches` and the four corner ratios.
+pub fn totals(matches: &DataFrame) -> Result<DataFrame> {
+    let sums = [
+        "total_ck_for",
+        "total_ck_against",
+        "shots_from_ck",
+        "shots_against_from_ck",
+        "xg",
+        "xg_against",
+    ]
+    .map(Agg::sum);
+
+    matches
+        .group_by(&["team"])?
+        .agg(sums.into_iter().chain([Agg::rows().alias("matches")]))?
+        .ratio("shots_per_corner", "shots_from_ck", "total_ck_for")?
+        .ratio("xg_per_shot", "xg", "shots_from_ck")?
+        .ratio(
+            "shots_against_per_corner",
+            "shots_against_from_ck",
+            "total_ck_against",
+        )?
+        .ratio("xg_against_per_shot", "xg_against", "shots_against_from_ck")
+}
+
+/// Which matches of the season to count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Window {
+    GameWeeks(GameWeekRange),
+    /// Each team's last `n` matches.
+    LastMatches(usize),
+    /// The first half of the game weeks present, with the middle week when there is
+    /// an odd number of them.
+    FirstHalf,
+    SecondHalf,
+}
+
+/// Drops every match outside `window`. Game-week keys are left in place, possibly empty,
+/// but a window without a single match is an [`MyError::EmptyDataset`].
+pub fn window(
+    mut input: HashMap<u32, Vec<CornerKickData>>,
+    window: Window,
+) -> Result<HashMap<u32, Vec<CornerKickData>>> {
+    let mut game_weeks = input
+        .values()
+        .flatten()
+        .map(|d| d.game_week)
+        .collect::<Vec<_>>();
+    game_weeks.sort_unstable();
+    game_weeks.dedup();
+
+    let last_of_first_half = game_weeks
+        .len()
+        .div_ceil(2)
+        .checked_sub(1)
+        .map(|idx| game_weeks[idx]);
+    let range = match (window, last_of_first_half) {
+        (Window::GameWeeks(range), _) => range,
+        (Window::FirstHalf, Some(gw)) => GameWeekRange::new(None, Some(gw)),
+        (Window::SecondHalf, Some(gw)) => GameWeekRange::new(Some(gw + 1), None),
+        _ => GameWeekRange::default(),
+    };
+
+    let recent = match window {
+        Window::LastMatches(n) => {
+            let mut played: HashMap<Teams, Vec<u32>> = HashMap::new();
+            for d in input.values().flatten() {
+                played.entry(d.team).or_default().push(d.game_week);
+            }
+            let recent = played
+                .into_iter()
+                .flat_map(|(team, mut weeks)| {
+                    weeks.sort_unstable_by(|a, b| b.cmp(a));
+                    weeks.into_iter().take(n).map(move |gw| (team, gw))
+                })
+                .collect::<HashSet<_>>();
+            Some(recent)
+        }
+        _ => None,
+    };
+
+    for week in input.values_mut() {
+        week.retain(|d| {
+            range.contains(d.game_week)
+                && recent
+                    .as_ref()
+                    .is_none_or(|recent| recent.contains(&(d.team, d.game_week)))
+        });
+    }
+
+    if input.values().all(Vec::is_empty) {
+        return Err(MyError::EmptyDataset { path: None });
+    }
+
+    Ok(input)
+}
+
+/// Totals over one rolling window, ending at `game_week`.
+#[derive(Debug, Clone, Serialize)]
+pub struct FormPoint {
+    pub game_week: u32,
+    pub totals: TeamCKData,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TeamForm {
+    pub team: Teams,
+    pub points: Vec<FormPoint>,
+}
+
+/// Per-team totals over every run of `size` consecutive matches, so ratios come out as
+/// sums over the window rather than means of per-match ratios.
+///
+/// A team's first point is its `size`-th match.
+pub fn rolling(input: &HashMap<u32, Vec<CornerKickData>>, size: NonZeroUsize) -> Vec<TeamForm> {
+    let size = size.get();
+
+    by_team(input)
+        .into_iter()
+        .map(|(team, matches)| {
+            let points = matches
+                .windows(size)
+                .map(|window| FormPoint {
+                    game_week: window[size - 1].game_week,
+                    totals: TeamCKData::sum(team, window.iter().copied()),
+                })
+                .collect();
+
+            TeamForm { team, points }
+        })
+        .collect()
+}
+
+/// One value per match for every team, e.g. `|d| d.xg` for corner xG per game week.
+pub fn per_match<F: Fn(&CornerKickData) -> f64>(
+    input: &HashMap<u32, Vec<CornerKickData>>,
+    value: F,
+) -> Vec<(Teams, WeekValues)> {
+    by_team(input)
+        .into_iter()
+        .map(|(team, matches)| {
+            let values = matches.iter().map(|d| (d.game_week, value(d))).collect();
+            (team, values)
+        })
+        .collect()
+}
+
+/// Points of a team column against two ratio columns of [`totals`].
+fn points(df: &DataFrame, x: &str, y: &str) -> Result<Vec<(Teams, f64, f64)>> {
+    Ok(df
+        .teams("team")?
+        .iter()
+        .zip(df.numbers(x)?.iter().zip(df.numbers(y)?))
+        .map(|(team, (x, y))| (*team, *x, *y))
+        .collect())
+}
+
+/// xG per shot against shots per corner, for corners taken, from [`totals`].
+pub fn attacking_chart(df: &DataFrame) -> Result<TeamScatter> {
+    Ok(TeamScatter::new("Attacking Corner Proficiency")
+        .x_desc("xG per Shot from Corner Kick")
+        .y_desc("Shot per Corner Kick")
+        .crosshair(Crosshair::Mean)
+        .shade_quadrant(Quadrant::TopRight, GREEN)
+        .shade_quadrant(Quadrant::BottomLeft, RED)
+        .corner_text(Quadrant::TopLeft, "Many shots, poor chances")
+        .corner_text(Quadrant::TopRight, "Efficient")
+        .corner_text(Quadrant::BottomLeft, "Wasteful")
+        .corner_text(Quadrant::BottomRight, "Few shots, good chances")
+        .iso_lines("xG per corner", ISO_XG_PER_CORNER)
+        .points(points(df, "xg_per_shot", "shots_per_corner")?))
+}
+
+/// The same for corners conceded.
+pub fn defensive_chart(df: &DataFrame) -> Result<TeamScatter> {
+    Ok(TeamScatter::new("Defensive Corner Proficiency")
+        .x_desc("xG per Shot Conceded from Corner Kick")
+        .y_desc("Shot Conceded per Corner Kick Faced")
+        .crosshair(Crosshair::Mean)
+        .shade_quadrant(Quadrant::TopRight, RED)
+        .shade_quadrant(Quadrant::BottomLeft, GREEN)
+        .corner_text(Quadrant::TopLeft, "Many shots, poor chances")
+        .corner_text(Quadrant::TopRight, "Vulnerable")
+        .corner_text(Quadrant::BottomLeft, "Solid")
+        .corner_text(Quadrant::BottomRight, "Few shots, good chances")
+        .iso_lines("xG conceded per corner", ISO_XG_PER_CORNER)
+        .margin(30)
+        .points(points(
+            df,
+            "xg_against_per_shot",
+            "shots_against_per_corner",
+        )?))
+}
+
+/// Corner xG and xG conceded as bars in club colours, one row per team of [`totals`] in
+/// its order.
+pub fn xg_chart(df: &DataFrame) -> Result<PanelReport> {
+    let teams = df.teams("team")?;
+    let values = |name: &str| -> Result<HashMap<Teams, f64>> {
+        Ok(teams
+            .iter()
+            .copied()
+            .zip(df.numbers(name)?.iter().copied())
+            .collect())
+    };
+
+    Ok(PanelReport::new(teams.to_vec())
+        .title("Corner xG per Team")
+        .panel(
+            MetricPanel::new("xG from Corner Kicks")
+                .x_desc("xG")
+                .series(BarSeries::by_team("xG", values("xg")?))
+                .bar_labels(),
+        )
+        .panel(
+            MetricPanel::new("xG Conceded from Corner Kicks")
+                .x_desc("xG Conceded")
+                .series(BarSeries::by_team("xG Conceded", values("xg_against")?))
+                .bar_labels(),
+        ))
+}
@@ -0,0 +1,317 @@
+use std::error::Error;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::{Fixture, Teams};
+
+pub type Result<T> = std::result::Result<T, MyError>;
+
+/// Everything that can go wrong loading data or drawing charts. Wrapped errors are kept
+/// as the [`Error::source`], together with the file they came from when it is known.
+#[derive(Debug)]
+pub enum MyError {
+    Io {
+        path: Option<PathBuf>,
+        source: io::Error,
+    },
+    /// Reading or writing a CSV file. The record is in `source.position()`.
+    Csv {
+        path: Option<PathBuf>,
+        source: csv::Error,
+    },
+    Image {
+        path: Option<PathBuf>,
+        source: image::ImageError,
+    },
+    Toml {
+        path: Option<PathBuf>,
+        source: toml::de::Error,
+    },
+    Json {
+        path: Option<PathBuf>,
+        source: serde_json::Error,
+    },
+    Plotters(Box<dyn Error + Send + Sync>),
+    #[cfg(feature = "arrow")]
+    Arrow {
+        path: Option<PathBuf>,
+        source: arrow_schema::ArrowError,
+    },
+    #[cfg(feature = "arrow")]
+    Parquet {
+        path: Option<PathBuf>,
+        source: parquet::errors::ParquetError,
+    },
+    #[cfg(feature = "polars")]
+    Polars {
+        path: Option<PathBuf>,
+        source: polars::error::PolarsError,
+    },
+    Registry(String),
+    InvalidFixture(String),
+    /// A cell that could not be parsed.
+    InvalidValue {
+        path: Option<PathBuf>,
+        row: u64,
+        column: &'static str,
+        value: String,
+    },
+    UnsupportedFormat(String),
+    /// A [`DataFrame`](crate::DataFrame) column that is missing, of the wrong type or of
+    /// the wrong length.
+    InvalidColumn(String),
+    UnknownTeam {
+        name: String,
+        suggestion: Option<Teams>,
+    },
+    UnknownGameState(String),
+    /// A row whose fixture cannot be joined: either `team` does not play in it, or there
+    /// is no row for `opponent`.
+    UnmatchedFixture {
+        path: Option<PathBuf>,
+        row: u64,
+        team: Teams,
+        fixture: Fixture,
+        opponent: Option<Teams>,
+    },
+    /// An input file without a single data row, or with none left after the game-week
+    /// and team selection.
+    EmptyDataset {
+        path: Option<PathBuf>,
+    },
+}
+
+impl MyError {
+    /// Records the file the error came from, unless it already has one.
+    pub fn with_path<P: AsRef<Path>>(mut self, file: P) -> Self {
+        match &mut self {
+            Self::Io { path, .. }
+            | Self::Csv { path, .. }
+            | Self::Image { path, .. }
+            | Self::Toml { path, .. }
+            | Self::Json { path, .. }
+            | Self::InvalidValue { path, .. }
+            | Self::UnmatchedFixture { path, .. }
+            | Self::EmptyDataset { path } => {
+                path.get_or_insert_with(|| file.as_ref().to_path_buf());
+            }
+            #[cfg(feature = "arrow")]
+            Self::Arrow { path, .. } | Self::Parquet { path, .. } => {
+                path.get_or_insert_with(|| file.as_ref().to_path_buf());
+            }
+            #[cfg(feature = "polars")]
+            Self::Polars { path, .. } => {
+                path.get_or_insert_with(|| file.as_ref().to_path_buf());
+            }
+            _ => {}
+        }
+        self
+    }
+
+    /// The file the error came from, if known.
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Self::Io { path, .. }
+            | Self::Csv { path, .. }
+            | Self::Image { path, .. }
+            | Self::Toml { path, .. }
+            | Self::Json { path, .. }
+            | Self::InvalidValue { path, .. }
+            | Self::UnmatchedFixture { path, .. }
+            | Self::EmptyDataset { path } => path.as_deref(),
+            #[cfg(feature = "arrow")]
+            Self::Arrow { path, .. } | Self::Parquet { path, .. } => path.as_deref(),
+            #[cfg(feature = "polars")]
+            Self::Polars { path, .. } => path.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// Line of a record in its file, counting the header as line 1.
+///
+/// `csv::Position::line` undercounts in files with `\r\n` line endings, which the exports
+/// have. No field spans several lines, so the record number is used instead.
+pub(crate) fn line_of(pos: &csv::Position) -> u64 {
+    pos.record() + 1
+}
+
+/// `in {path}` or nothing, to follow a message.
+struct InFile<'a>(Option<&'a Path>);
+
+impl std::fmt::Display for InFile<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(path) => write!(f, " in {}", path.display()),
+            None => Ok(()),
+        }
+    }
+}
+
+impl std::fmt::Display for MyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let file = InFile(self.path());
+        match self {
+            Self::Io {
+                path: Some(path), ..
+            } => write!(f, "Could not access {}", path.display()),
+            Self::Io { path: None, .. } => write!(f, "I/O error"),
+            Self::Csv { source, .. } => match source.position() {
+                Some(pos) => write!(f, "Invalid CSV on line {}{file}", line_of(pos)),
+                None => write!(f, "Invalid CSV{file}"),
+            },
+            Self::Image {
+                path: Some(path), ..
+            } => {
+                write!(f, "Could not decode image {}", path.display())
+            }
+            Self::Image { path: None, .. } => write!(f, "Could not decode image"),
+            Self::Toml { .. } => write!(f, "Invalid team registry{file}"),
+            Self::Json { .. } => write!(f, "Could not write JSON{file}"),
+            Self::Plotters(_) => write!(f, "Could not draw chart"),
+            #[cfg(feature = "arrow")]
+            Self::Arrow { .. } => write!(f, "Could not build Arrow table{file}"),
+            #[cfg(feature = "arrow")]
+            Self::Parquet { .. } => write!(f, "Could not write Parquet{file}"),
+            #[cfg(feature = "polars")]
+            Self::Polars { .. } => write!(f, "Polars error{file}"),
+            Self::Registry(err) => write!(f, "{err}"),
+            Self::InvalidFixture(err) => write!(f, "{err}"),
+            Self::InvalidValue {
+                row, column, value, ..
+            } => write!(f, "Invalid {column} value {value:?} on line {row}{file}"),
+            Self::UnsupportedFormat(err) => write!(f, "{err}"),
+            Self::InvalidColumn(err) => write!(f, "{err}"),
+            Self::UnknownTeam {
+                name,
+                suggestion: Some(team),
+            } => write!(f, "Unknown team {name:?}, did you mean {:?}?", team.name()),
+            Self::UnknownTeam {
+                name,
+                suggestion: None,
+            } => write!(f, "Unknown team {name:?}"),
+            Self::UnknownGameState(state) => write!(
+                f,
+                "Unknown game state {state:?}, expected Winning, Drawing or Losing"
+            ),
+            Self::UnmatchedFixture {
+                row,
+                team,
+                fixture,
+                opponent: Some(opponent),
+                ..
+            } => {
+                write!(f, "No {opponent} row for {fixture}")?;
+                if let Some(game_week) = fixture.game_week {
+                    write!(f, " in game week {game_week}")?;
+                }
+                write!(f, ", which {team} plays on line {row}{file}")
+            }
+            Self::UnmatchedFixture {
+                row,
+                team,
+                fixture,
+                opponent: None,
+                ..
+            } => write!(f, "{team} does not play in {fixture} on line {row}{file}"),
+            Self::EmptyDataset { path: Some(path) } => write!(f, "No rows in {}", path.display()),
+            Self::EmptyDataset { path: None } => write!(f, "No rows in dataset"),
+        }
+    }
+}
+
+impl Error for MyError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            Self::Csv { source, .. } => Some(source),
+            Self::Image { source, .. } => Some(source),
+            Self::Toml { source, .. } => Some(source),
+            Self::Json { source, .. } => Some(source),
+            Self::Plotters(source) => Some(source.as_ref()),
+            #[cfg(feature = "arrow")]
+            Self::Arrow { source, .. } => Some(source),
+            #[cfg(feature = "arrow")]
+            Self::Parquet { source, .. } => Some(source),
+            #[cfg(feature = "polars")]
+            Self::Polars { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for MyError {
+    fn from(source: io::Error) -> Self {
+        Self::Io { path: None, source }
+    }
+}
+
+impl From<csv::Error> for MyError {
+    fn from(source: csv::Error) -> Self {
+        if !source.is_io_error() {
+            return Self::Csv { path: None, source };
+        }
+        match source.into_kind() {
+            csv::ErrorKind::Io(source) => source.into(),
+            _ => unreachable!("checked to be an I/O error"),
+        }
+    }
+}
+
+impl From<image::ImageError> for MyError {
+    fn from(source: image::ImageError) -> Self {
+        Self::Image { path: None, source }
+    }
+}
+
+impl From<toml::de::Error> for MyError {
+    fn from(source: toml::de::Error) -> Self {
+        Self::Toml { path: None, source }
+    }
+}
+
+#[cfg(feature = "arrow")]
+impl From<arrow_schema::ArrowError> for MyError {
+    fn from(source: arrow_schema::ArrowError) -> Self {
+        Self::Arrow { path: None, source }
+    }
+}
+
+#[cfg(feature = "arrow")]
+impl From<parquet::errors::ParquetError> for MyError {
+    fn from(source: parquet::errors::ParquetError) -> Self {
+        Self::Parquet { path: None, source }
+    }
+}
+
+#[cfg(feature = "polars")]
+impl From<polars::error::PolarsError> for MyError {
+    fn from(source: polars::error::PolarsError) -> Self {
+        Self::Polars { path: None, source }
+    }
+}
+
+impl From<serde_json::Error> for MyError {
+    fn from(source: serde_json::Error) -> Self {
+        Self::Json { path: None, source }
+    }
+}
+
+impl<E: Error + Send + Sync + 'static> From<plotters::drawing::DrawingAreaErrorKind<E>>
+    for MyError
+{
+    fn from(value: plotters::drawing::DrawingAreaErrorKind<E>) -> Self {
+        Self::Plotters(Box::new(value))
+    }
+}
+
+/// Adds the file an error came from, as in `fs::read(path).with_path(path)?`.
+pub trait ResultExt<T> {
+    fn with_path<P: AsRef<Path>>(self, path: P) -> Result<T>;
+}
+
+impl<T, E: Into<MyError>> ResultExt<T> for std::result::Result<T, E> {
+    fn with_path<P: AsRef<Path>>(self, path: P) -> Result<T> {
+        self.map_err(|err| err.into().with_path(path))
+    }
+}
@@ -0,0 +1,131 @@
+//! Parquet and Arrow IPC files of [`DataFrame`]s, for DuckDB and notebooks. Needs the
+//! `arrow` feature.
+//!
+//! Columns keep their frame names and order. Numbers are written as nullable `Float64`
+//! with NaN as null, integers such as counts and game weeks as `Int64`, and team and text
+//! columns as `Utf8`, teams by their registry name.
+
+use std::{fs::File, path::Path, str::FromStr, sync::Arc};
+
+use arrow_array::{ArrayRef, Float64Array, Int64Array, RecordBatch, StringArray};
+use arrow_schema::{DataType, Field, Schema};
+use parquet::arrow::ArrowWriter;
+
+use crate::{Column, DataFrame, MyError, Result, ResultExt};
+
+/// File format of an exported table, picked from the output path's extension.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TableFormat {
+    #[default]
+    Parquet,
+    /// The Arrow IPC file format, also known as Feather v2.
+    Ipc,
+}
+
+impl TableFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Parquet => "parquet",
+            Self::Ipc => "arrow",
+        }
+    }
+
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .ok_or_else(|| {
+                MyError::UnsupportedFormat(format!("No file extension on {}", path.display()))
+            })?
+            .parse()
+    }
+}
+
+impl FromStr for TableFormat {
+    type Err = MyError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "parquet" => Ok(Self::Parquet),
+            "ipc" | "arrow" | "feather" => Ok(Self::Ipc),
+            other => Err(MyError::UnsupportedFormat(format!(
+                "Unsupported table format {other:?}, expected parquet or ipc"
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for TableFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.extension())
+    }
+}
+
+pub fn schema(df: &DataFrame) -> Result<Schema> {
+    let fields = df
+        .names()
+        .iter()
+        .map(|name| {
+            let dtype = match df.column(name)? {
+                Column::Number(_) => DataType::Float64,
+                Column::Integer(_) => DataType::Int64,
+                Column::Team(_) | Column::Text(_) => DataType::Utf8,
+            };
+            Ok(Field::new(name, dtype, true))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Schema::new(fields))
+}
+
+pub fn record_batch(df: &DataFrame) -> Result<RecordBatch> {
+    let columns = df
+        .names()
+        .iter()
+        .map(|name| {
+            let array: ArrayRef = match df.column(name)? {
+                Column::Number(values) => Arc::new(
+                    values
+                        .iter()
+                        .map(|value| (!value.is_nan()).then_some(*value))
+                        .collect::<Float64Array>(),
+                ),
+                Column::Integer(values) => Arc::new(Int64Array::from(values.clone())),
+                Column::Team(values) => Arc::new(
+                    values
+                        .iter()
+                        .map(|team| Some(team.name()))
+                        .collect::<StringArray>(),
+                ),
+                Column::Text(values) => Arc::new(StringArray::from_iter_values(values)),
+            };
+            Ok(array)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(RecordBatch::try_new(Arc::new(schema(df)?), columns)?)
+}
+
+/// Writes `df` to `path` as Parquet or Arrow IPC, depending on its extension.
+pub fn write<P: AsRef<Path>>(df: &DataFrame, path: P) -> Result<()> {
+    let path = path.as_ref();
+    let format = TableFormat::from_path(path)?;
+    let batch = record_batch(df)?;
+    let file = File::create(path).with_path(path)?;
+
+    match format {
+        TableFormat::Parquet => {
+            let mut writer = ArrowWriter::try_new(file, batch.schema(), None).with_path(path)?;
+            writer.write(&batch).with_path(path)?;
+            writer.close().with_path(path)?;
+        }
+        TableFormat::Ipc => {
+            let mut writer =
+                arrow_ipc::writer::FileWriter::try_new(file, &batch.schema()).with_path(path)?;
+            writer.write(&batch).with_path(path)?;
+            writer.finish().with_path(path)?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,371 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{registry, MyError, Result, TeamRegistry, Teams};
+
+const SEPARATORS: [&str; 4] = [" vs. ", " vs ", " v ", "-"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Venue {
+    Home,
+    Away,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub struct Score {
+    pub home: u8,
+    pub away: u8,
+}
+
+/// A single match between two teams, as written in the `game`/`Match` columns.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Fixture {
+    pub home: Teams,
+    pub away: Teams,
+    pub game_week: Option<u32>,
+    pub date: Option<String>,
+    pub score: Option<Score>,
+}
+
+impl Fixture {
+    pub fn new(home: Teams, away: Teams) -> Self {
+        Self {
+            home,
+            away,
+            game_week: None,
+            date: None,
+            score: None,
+        }
+    }
+
+    pub fn with_game_week(mut self, game_week: u32) -> Self {
+        self.game_week = Some(game_week);
+        self
+    }
+
+    pub fn with_date(mut self, date: impl Into<String>) -> Self {
+        self.date = Some(date.into());
+        self
+    }
+
+    pub fn with_score(mut self, score: Score) -> Self {
+        self.score = Some(score);
+        self
+    }
+
+    /// Parses `"Home vs Away"`, `"Home - Away"` or `"Home 2-1 Away"`.
+    ///
+    /// Every separator position is tried, so team names containing hyphens still
+    /// resolve as long as only one split yields two known teams.
+    pub fn parse(input: &str) -> Result<Self> {
+        Self::parse_in(input, registry())
+    }
+
+    fn parse_in(input: &str, teams: &TeamRegistry) -> Result<Self> {
+        let lowercase = input.to_ascii_lowercase();
+        let mut candidates: Vec<Fixture> = Vec::new();
+        let mut first_error = None;
+
+        for separator in SEPARATORS {
+            for (pos, _) in lowercase.match_indices(separator) {
+                let home = &input[..pos];
+                let away = &input[pos + separator.len()..];
+                let parsed = match split_score(home, away) {
+                    Some((home, away, score)) => {
+                        Self::from_sides(home, away, teams).map(|fixture| fixture.with_score(score))
+                    }
+                    None => Self::from_sides(home, away, teams),
+                };
+
+                match parsed {
+                    Ok(fixture) if !candidates.contains(&fixture) => candidates.push(fixture),
+                    Ok(_) => {}
+                    Err(err) => {
+                        first_error.get_or_insert(err);
+                    }
+                }
+            }
+        }
+
+        match candidates.len() {
+            1 => Ok(candidates.remove(0)),
+            0 => Err(first_error
+                .unwrap_or_else(|| MyError::InvalidFixture(format!("No separator in {input:?}")))),
+            _ => Err(MyError::InvalidFixture(format!(
+                "Ambiguous fixture {input:?}"
+            ))),
+        }
+    }
+
+    fn from_sides(home: &str, away: &str, teams: &TeamRegistry) -> Result<Self> {
+        let home = teams.parse(home.trim())?;
+        let away = teams.parse(away.trim())?;
+        if home == away {
+            return Err(MyError::InvalidFixture(format!(
+                "{home} cannot play itself"
+            )));
+        }
+
+        Ok(Self::new(home, away))
+    }
+
+    pub fn teams(&self) -> [Teams; 2] {
+        [self.home, self.away]
+    }
+
+    pub fn involves(&self, team: Teams) -> bool {
+        self.home == team || self.away == team
+    }
+
+    pub fn venue_of(&self, team: Teams) -> Option<Venue> {
+        if team == self.home {
+            Some(Venue::Home)
+        } else if team == self.away {
+            Some(Venue::Away)
+        } else {
+            None
+        }
+    }
+
+    pub fn opponent_of(&self, team: Teams) -> Option<Teams> {
+        match self.venue_of(team)? {
+            Venue::Home => Some(self.away),
+            Venue::Away => Some(self.home),
+        }
+    }
+}
+
+/// Inclusive range of game weeks with either end open, written as `"1-17"`, `"18-"`,
+/// `"-5"` or `"5"`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GameWeekRange {
+    pub first: Option<u32>,
+    pub last: Option<u32>,
+}
+
+impl GameWeekRange {
+    pub fn new(first: Option<u32>, last: Option<u32>) -> Self {
+        Self { first, last }
+    }
+
+    pub fn contains(&self, game_week: u32) -> bool {
+        self.first.is_none_or(|first| game_week >= first)
+            && self.last.is_none_or(|last| game_week <= last)
+    }
+}
+
+impl std::str::FromStr for GameWeekRange {
+    type Err = MyError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let bound = |value: &str| -> Result<Option<u32>> {
+            match value.trim() {
+                "" => Ok(None),
+                value => value.parse().map(Some).map_err(|_| {
+                    MyError::InvalidFixture(format!("Invalid game week {value:?} in {s:?}"))
+                }),
+            }
+        };
+
+        let range = match s.split_once('-') {
+            Some((first, last)) => Self::new(bound(first)?, bound(last)?),
+            None => {
+                let week = bound(s)?;
+                Self::new(week, week)
+            }
+        };
+        if let (Some(first), Some(last)) = (range.first, range.last) {
+            if first > last {
+                return Err(MyError::InvalidFixture(format!(
+                    "Game-week range {s:?} ends before it starts"
+                )));
+            }
+        }
+
+        Ok(range)
+    }
+}
+
+impl std::fmt::Display for GameWeekRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let bound = |week: Option<u32>| week.map(|week| week.to_string()).unwrap_or_default();
+        write!(f, "{}-{}", bound(self.first), bound(self.last))
+    }
+}
+
+fn split_score<'a>(home: &'a str, away: &'a str) -> Option<(&'a str, &'a str, Score)> {
+    let (home_name, home_goals) = home.trim_end().rsplit_once(char::is_whitespace)?;
+    let (away_goals, away_name) = away.trim_start().split_once(char::is_whitespace)?;
+    let score = Score {
+        home: home_goals.parse().ok()?,
+        away: away_goals.parse().ok()?,
+    };
+
+    Some((home_name, away_name, score))
+}
+
+impl std::str::FromStr for Fixture {
+    type Err = MyError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl std::fmt::Display for Fixture {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.score {
+            Some(score) => write!(
+                f,
+                "{} {} - {} {}",
+                self.home, score.home, score.away, self.away
+            ),
+            None => write!(f, "{} vs {}", self.home, self.away),
+        }
+    }
+}
+
+/// As its [`Display`](std::fmt::Display) text, e.g. `"AREMA FC vs Dewa United FC"`.
+impl Serialize for Fixture {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Fixture {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let game = String::deserialize(deserializer)?;
+        game.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn team(name: &str) -> Teams {
+        registry().resolve(name).expect("bundled team")
+    }
+
+    /// Teams with hyphens in their names, which the bundled league has none of.
+    fn hyphenated() -> TeamRegistry {
+        let team = |name: &str, short: &str| {
+            format!(
+                "[[team]]\nname = \"{name}\"\nshort = \"{short}\"\nprimary = \"#000000\"\nsecondary = \"#ffffff\"\n"
+            )
+        };
+        let toml = [
+            "league = \"Test\"\n".to_string(),
+            team("Al-Hilal", "HIL"),
+            team("Al-Nassr", "NAS"),
+            team("Sun", "SUN"),
+            team("Sun-Ray", "SRY"),
+            team("Ray-Star", "RST"),
+            team("Star", "STR"),
+        ]
+        .concat();
+        TeamRegistry::from_toml_str(&toml).expect("test registry is valid")
+    }
+
+    #[test]
+    fn parses_every_separator() {
+        for input in [
+            "AREMA FC - Dewa United FC",
+            "AREMA FC-Dewa United FC",
+            "AREMA FC vs Dewa United FC",
+            "AREMA FC vs. Dewa United FC",
+            "AREMA FC v Dewa United FC",
+        ] {
+            let fixture = Fixture::parse(input).unwrap();
+            assert_eq!(fixture.home, team("AREMA FC"), "{input}");
+            assert_eq!(fixture.away, team("Dewa United FC"), "{input}");
+            assert_eq!(fixture.score, None, "{input}");
+        }
+    }
+
+    #[test]
+    fn ignores_case_and_resolves_aliases() {
+        let fixture = Fixture::parse("Arema FC VS persib").unwrap();
+        assert_eq!(fixture.home, team("AREMA FC"));
+        assert_eq!(fixture.away, team("PERSIB Bandung"));
+    }
+
+    #[test]
+    fn parses_a_score() {
+        let fixture = Fixture::parse("PSM Makassar 2-1 PERSIS Solo").unwrap();
+        assert_eq!(fixture.home, team("PSM Makassar"));
+        assert_eq!(fixture.away, team("PERSIS Solo"));
+        assert_eq!(fixture.score, Some(Score { home: 2, away: 1 }));
+    }
+
+    #[test]
+    fn splits_hyphenated_names() {
+        let teams = hyphenated();
+        for input in [
+            "Al-Hilal - Al-Nassr",
+            "Al-Hilal-Al-Nassr",
+            "al-hilal vs AL-NASSR",
+        ] {
+            let fixture = Fixture::parse_in(input, &teams).unwrap();
+            assert_eq!(fixture.home, teams.resolve("Al-Hilal").unwrap(), "{input}");
+            assert_eq!(fixture.away, teams.resolve("Al-Nassr").unwrap(), "{input}");
+        }
+    }
+
+    #[test]
+    fn rejects_ambiguous_splits() {
+        // "Sun" vs "Ray-Star" and "Sun-Ray" vs "Star" are both fixtures.
+        let err = Fixture::parse_in("Sun-Ray-Star", &hyphenated()).unwrap_err();
+        assert!(matches!(err, MyError::InvalidFixture(_)), "{err}");
+    }
+
+    #[test]
+    fn rejects_unknown_teams_without_the_separator() {
+        let err = Fixture::parse("Persk Kediri vs AREMA FC").unwrap_err();
+        match err {
+            MyError::UnknownTeam { name, suggestion } => {
+                assert_eq!(name, "Persk Kediri");
+                assert_eq!(suggestion, Some(team("PERSIK Kediri")));
+            }
+            err => panic!("unexpected error {err}"),
+        }
+    }
+
+    #[test]
+    fn rejects_missing_separators_and_self_play() {
+        assert!(matches!(
+            Fixture::parse("AREMA FC"),
+            Err(MyError::InvalidFixture(_))
+        ));
+        assert!(matches!(
+            Fixture::parse("AREMA FC vs Arema"),
+            Err(MyError::InvalidFixture(_))
+        ));
+    }
+
+    #[test]
+    fn game_week_ranges() {
+        let range = |s: &str| s.parse::<GameWeekRange>().unwrap();
+        assert_eq!(range("1-17"), GameWeekRange::new(Some(1), Some(17)));
+        assert_eq!(range(" 3 - 7 "), GameWeekRange::new(Some(3), Some(7)));
+        assert_eq!(range("18-"), GameWeekRange::new(Some(18), None));
+        assert_eq!(range("-5"), GameWeekRange::new(None, Some(5)));
+        assert_eq!(range("5"), GameWeekRange::new(Some(5), Some(5)));
+        assert_eq!(range("-"), GameWeekRange::default());
+
+        assert!(range("18-").contains(18) && range("18-").contains(34));
+        assert!(!range("18-").contains(17));
+        assert!(range("-5").contains(1) && !range("-5").contains(6));
+
+        for input in ["17-1", "x", "1-x"] {
+            assert!(input.parse::<GameWeekRange>().is_err(), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn game_week_range_round_trips() {
+        for input in ["1-17", "18-", "-5", "5-5"] {
+            assert_eq!(input.parse::<GameWeekRange>().unwrap().to_string(), input);
+        }
+    }
+}
@@ -0,0 +1,676 @@
+use std::{cmp::Ordering, collections::HashMap, fs::File, io::BufWriter, path::Path};
+
+use serde::{ser::SerializeMap, Serialize, Serializer};
+
+use crate::{game_state::Ratio, MyError, Result, ResultExt, Teams};
+
+/// One column of a [`DataFrame`]. Numbers use NaN for a missing value; integers, for
+/// counts and game weeks, are never missing.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Column {
+    Number(Vec<f64>),
+    Integer(Vec<i64>),
+    Team(Vec<Teams>),
+    Text(Vec<String>),
+}
+
+impl Column {
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Number(values) => values.len(),
+            Self::Integer(values) => values.len(),
+            Self::Team(values) => values.len(),
+            Self::Text(values) => values.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Number(_) => "numeric",
+            Self::Integer(_) => "integer",
+            Self::Team(_) => "team",
+            Self::Text(_) => "text",
+        }
+    }
+
+    fn take(&self, rows: &[usize]) -> Self {
+        match self {
+            Self::Number(values) => Self::Number(rows.iter().map(|idx| values[*idx]).collect()),
+            Self::Integer(values) => Self::Integer(rows.iter().map(|idx| values[*idx]).collect()),
+            Self::Team(values) => Self::Team(rows.iter().map(|idx| values[*idx]).collect()),
+            Self::Text(values) => Self::Text(rows.iter().map(|idx| values[*idx].clone()).collect()),
+        }
+    }
+
+    fn extend(&mut self, other: &Column) -> bool {
+        match (self, other) {
+            (Self::Number(values), Self::Number(other)) => values.extend(other),
+            (Self::Integer(values), Self::Integer(other)) => values.extend(other),
+            (Self::Team(values), Self::Team(other)) => values.extend(other),
+            (Self::Text(values), Self::Text(other)) => values.extend(other.iter().cloned()),
+            _ => return false,
+        }
+        true
+    }
+
+    /// The cell as CSV text, NaN as an empty cell.
+    fn cell(&self, idx: usize) -> String {
+        match self {
+            Self::Number(values) if values[idx].is_nan() => String::new(),
+            Self::Number(values) => values[idx].to_string(),
+            Self::Integer(values) => values[idx].to_string(),
+            Self::Team(values) => values[idx].name().to_string(),
+            Self::Text(values) => values[idx].clone(),
+        }
+    }
+
+    fn key(&self, idx: usize) -> Key<'_> {
+        match self {
+            Self::Number(values) => Key::Number(values[idx].to_bits()),
+            Self::Integer(values) => Key::Integer(values[idx]),
+            Self::Team(values) => Key::Team(values[idx]),
+            Self::Text(values) => Key::Text(&values[idx]),
+        }
+    }
+
+    fn compare(&self, a: usize, b: usize) -> Ordering {
+        match self {
+            Self::Number(values) => values[a].total_cmp(&values[b]),
+            Self::Integer(values) => values[a].cmp(&values[b]),
+            Self::Team(values) => values[a].name().cmp(values[b].name()),
+            Self::Text(values) => values[a].cmp(&values[b]),
+        }
+    }
+}
+
+impl From<Vec<f64>> for Column {
+    fn from(values: Vec<f64>) -> Self {
+        Self::Number(values)
+    }
+}
+
+impl From<Vec<i64>> for Column {
+    fn from(values: Vec<i64>) -> Self {
+        Self::Integer(values)
+    }
+}
+
+impl From<Vec<Teams>> for Column {
+    fn from(values: Vec<Teams>) -> Self {
+        Self::Team(values)
+    }
+}
+
+impl From<Vec<String>> for Column {
+    fn from(values: Vec<String>) -> Self {
+        Self::Text(values)
+    }
+}
+
+/// A group-by key cell. Numbers are compared by their bits, so NaN groups with NaN.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Key<'a> {
+    Number(u64),
+    Integer(i64),
+    Team(Teams),
+    Text(&'a str),
+}
+
+/// A small table of named, equally long columns.
+///
+/// Every operation returns a new frame, so calls chain:
+/// `df.filter(..).group_by(&["team"])?.agg([Agg::sum("xg")])?.sort_by("xg")?`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DataFrame {
+    names: Vec<String>,
+    columns: Vec<Column>,
+}
+
+impl DataFrame {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a column, or replaces the one with the same name.
+    pub fn with_column(
+        mut self,
+        name: impl Into<String>,
+        column: impl Into<Column>,
+    ) -> Result<Self> {
+        let (name, column) = (name.into(), column.into());
+        if !self.columns.is_empty() && column.len() != self.len() {
+            return Err(MyError::InvalidColumn(format!(
+                "Column {name:?} has {} rows, the frame has {}",
+                column.len(),
+                self.len()
+            )));
+        }
+
+        match self.position(&name) {
+            Some(idx) => self.columns[idx] = column,
+            None => {
+                self.names.push(name);
+                self.columns.push(column);
+            }
+        }
+        Ok(self)
+    }
+
+    pub fn len(&self) -> usize {
+        self.columns.first().map_or(0, Column::len)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|column| column == name)
+    }
+
+    pub fn column(&self, name: &str) -> Result<&Column> {
+        self.position(name)
+            .map(|idx| &self.columns[idx])
+            .ok_or_else(|| MyError::InvalidColumn(format!("No column {name:?}")))
+    }
+
+    fn mismatch(name: &str, column: &Column, expected: &str) -> MyError {
+        MyError::InvalidColumn(format!(
+            "Column {name:?} is {}, not {expected}",
+            column.kind()
+        ))
+    }
+
+    pub fn numbers(&self, name: &str) -> Result<&[f64]> {
+        match self.column(name)? {
+            Column::Number(values) => Ok(values),
+            column => Err(Self::mismatch(name, column, "numeric")),
+        }
+    }
+
+    pub fn integers(&self, name: &str) -> Result<&[i64]> {
+        match self.column(name)? {
+            Column::Integer(values) => Ok(values),
+            column => Err(Self::mismatch(name, column, "integer")),
+        }
+    }
+
+    /// A numeric or integer column as numbers.
+    fn values(&self, name: &str) -> Result<Vec<f64>> {
+        match self.column(name)? {
+            Column::Number(values) => Ok(values.clone()),
+            Column::Integer(values) => Ok(values.iter().map(|value| *value as f64).collect()),
+            column => Err(Self::mismatch(name, column, "numeric")),
+        }
+    }
+
+    pub fn teams(&self, name: &str) -> Result<&[Teams]> {
+        match self.column(name)? {
+            Column::Team(values) => Ok(values),
+            column => Err(Self::mismatch(name, column, "team")),
+        }
+    }
+
+    pub fn texts(&self, name: &str) -> Result<&[String]> {
+        match self.column(name)? {
+            Column::Text(values) => Ok(values),
+            column => Err(Self::mismatch(name, column, "text")),
+        }
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = Row<'_>> {
+        (0..self.len()).map(|idx| Row { frame: self, idx })
+    }
+
+    fn take(&self, rows: &[usize]) -> Self {
+        Self {
+            names: self.names.clone(),
+            columns: self
+                .columns
+                .iter()
+                .map(|column| column.take(rows))
+                .collect(),
+        }
+    }
+
+    /// The rows for which `keep` is true.
+    pub fn filter<F: Fn(Row<'_>) -> bool>(&self, keep: F) -> Self {
+        let rows = self
+            .rows()
+            .filter(|row| keep(*row))
+            .map(|row| row.idx)
+            .collect::<Vec<_>>();
+        self.take(&rows)
+    }
+
+    /// A numeric column computed from every row.
+    pub fn derive<F: Fn(Row<'_>) -> f64>(self, name: impl Into<String>, value: F) -> Result<Self> {
+        let values = self.rows().map(value).collect::<Vec<_>>();
+        self.with_column(name, values)
+    }
+
+    /// `numerator / denominator`, zero where the denominator is zero. Either can be an
+    /// integer column.
+    pub fn ratio(
+        self,
+        name: impl Into<String>,
+        numerator: &str,
+        denominator: &str,
+    ) -> Result<Self> {
+        let values = self
+            .values(numerator)?
+            .into_iter()
+            .zip(self.values(denominator)?)
+            .map(|(numerator, denominator)| Ratio::new(numerator, denominator).value())
+            .collect::<Vec<_>>();
+        self.with_column(name, values)
+    }
+
+    /// Stable sort, ascending. Teams sort by name and NaN sorts last.
+    pub fn sort_by(&self, name: &str) -> Result<Self> {
+        let column = self.column(name)?;
+        let mut rows = (0..self.len()).collect::<Vec<_>>();
+        rows.sort_by(|a, b| column.compare(*a, *b));
+        Ok(self.take(&rows))
+    }
+
+    /// Stable sort, descending, with NaN first.
+    pub fn sort_by_desc(&self, name: &str) -> Result<Self> {
+        let column = self.column(name)?;
+        let mut rows = (0..self.len()).collect::<Vec<_>>();
+        rows.sort_by(|a, b| column.compare(*b, *a));
+        Ok(self.take(&rows))
+    }
+
+    /// The `names` columns, in that order.
+    pub fn select(&self, names: &[&str]) -> Result<Self> {
+        names.iter().try_fold(Self::new(), |frame, name| {
+            frame.with_column(*name, self.column(name)?.clone())
+        })
+    }
+
+    /// The rows of `other` after these. Both need the same columns, of the same types.
+    pub fn vstack(mut self, other: &DataFrame) -> Result<Self> {
+        if self.names != other.names {
+            return Err(MyError::InvalidColumn(format!(
+                "Cannot stack columns {:?} on {:?}",
+                other.names, self.names
+            )));
+        }
+        for (name, (column, other)) in self
+            .names
+            .iter()
+            .zip(self.columns.iter_mut().zip(&other.columns))
+        {
+            if !column.extend(other) {
+                return Err(Self::mismatch(name, other, column.kind()));
+            }
+        }
+        Ok(self)
+    }
+
+    /// Writes a header and one record per row, with NaN as an empty cell.
+    pub fn write_csv<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let mut writer = csv::Writer::from_path(path).with_path(path)?;
+        writer.write_record(&self.names).with_path(path)?;
+        for idx in 0..self.len() {
+            writer
+                .write_record(self.columns.iter().map(|column| column.cell(idx)))
+                .with_path(path)?;
+        }
+        writer.flush().with_path(path)
+    }
+
+    /// Writes an array with one object per row, with NaN as null.
+    pub fn write_json<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let file = File::create(path).with_path(path)?;
+        serde_json::to_writer_pretty(BufWriter::new(file), self).with_path(path)
+    }
+
+    /// Groups rows with equal values in every `keys` column, in order of first appearance.
+    pub fn group_by(&self, keys: &[&str]) -> Result<GroupBy<'_>> {
+        let key_columns = keys
+            .iter()
+            .map(|name| self.column(name))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut positions: HashMap<Vec<Key<'_>>, usize> = HashMap::new();
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        for idx in 0..self.len() {
+            let key = key_columns.iter().map(|column| column.key(idx)).collect();
+            let group = *positions.entry(key).or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+            groups[group].push(idx);
+        }
+
+        Ok(GroupBy {
+            frame: self,
+            keys: keys.iter().map(|key| key.to_string()).collect(),
+            groups,
+        })
+    }
+}
+
+/// One row of a [`DataFrame`], for [`DataFrame::filter`] and [`DataFrame::derive`].
+///
+/// Getters return `None` for a missing column or one of another type.
+#[derive(Debug, Clone, Copy)]
+pub struct Row<'a> {
+    frame: &'a DataFrame,
+    idx: usize,
+}
+
+impl<'a> Row<'a> {
+    pub fn number(&self, name: &str) -> Option<f64> {
+        self.frame.numbers(name).ok().map(|values| values[self.idx])
+    }
+
+    pub fn integer(&self, name: &str) -> Option<i64> {
+        self.frame
+            .integers(name)
+            .ok()
+            .map(|values| values[self.idx])
+    }
+
+    pub fn team(&self, name: &str) -> Option<Teams> {
+        self.frame.teams(name).ok().map(|values| values[self.idx])
+    }
+
+    pub fn text(&self, name: &str) -> Option<&'a str> {
+        self.frame
+            .texts(name)
+            .ok()
+            .map(|values| values[self.idx].as_str())
+    }
+}
+
+/// A sequence of row objects, as in [`DataFrame::write_json`].
+impl Serialize for DataFrame {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.rows())
+    }
+}
+
+impl Serialize for Row<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.frame.names.len()))?;
+        for (name, column) in self.frame.names.iter().zip(&self.frame.columns) {
+            match column {
+                Column::Number(values) => map.serialize_entry(name, &values[self.idx])?,
+                Column::Integer(values) => map.serialize_entry(name, &values[self.idx])?,
+                Column::Team(values) => map.serialize_entry(name, &values[self.idx])?,
+                Column::Text(values) => map.serialize_entry(name, &values[self.idx])?,
+            }
+        }
+        map.end()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GroupBy<'a> {
+    frame: &'a DataFrame,
+    keys: Vec<String>,
+    groups: Vec<Vec<usize>>,
+}
+
+impl GroupBy<'_> {
+    /// One row per group, with the key columns followed by one column per aggregation.
+    ///
+    /// Counts and sums of integer columns are integers, everything else a number.
+    pub fn agg<I: IntoIterator<Item = Agg>>(&self, aggs: I) -> Result<DataFrame> {
+        let first_rows = self.groups.iter().map(|rows| rows[0]).collect::<Vec<_>>();
+        let mut frame = DataFrame::new();
+        for key in &self.keys {
+            frame = frame.with_column(key.clone(), self.frame.column(key)?.take(&first_rows))?;
+        }
+
+        for agg in aggs {
+            let column = match &agg.column {
+                None => Column::Integer(self.groups.iter().map(|rows| rows.len() as i64).collect()),
+                Some(name) => match (self.frame.column(name)?, agg.op) {
+                    (Column::Integer(values), AggOp::Sum) => Column::Integer(
+                        self.groups
+                            .iter()
+                            .map(|rows| rows.iter().map(|idx| values[*idx]).sum())
+                            .collect(),
+                    ),
+                    (_, op) => {
+                        let values = self.frame.values(name)?;
+                        let aggregated = self
+                            .groups
+                            .iter()
+                            .map(|rows| op.apply(rows.iter().map(|idx| values[*idx])))
+                            .collect::<Vec<_>>();
+                        match op {
+                            AggOp::Count => {
+                                Column::Integer(aggregated.into_iter().map(|n| n as i64).collect())
+                            }
+                            _ => Column::Number(aggregated),
+                        }
+                    }
+                },
+            };
+            frame = frame.with_column(agg.name, column)?;
+        }
+
+        Ok(frame)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AggOp {
+    Sum,
+    Mean,
+    Median,
+    Count,
+}
+
+impl AggOp {
+    /// Skips NaN. An empty sum is zero, an empty mean or median NaN.
+    fn apply<I: Iterator<Item = f64>>(self, values: I) -> f64 {
+        let values = values.filter(|value| !value.is_nan());
+        match self {
+            Self::Sum => values.sum(),
+            Self::Count => values.count() as f64,
+            Self::Mean => {
+                let (sum, count) =
+                    values.fold((0., 0usize), |(sum, count), value| (sum + value, count + 1));
+                if count == 0 {
+                    f64::NAN
+                } else {
+                    sum / count as f64
+                }
+            }
+            Self::Median => {
+                let mut values = values.collect::<Vec<_>>();
+                values.sort_by(f64::total_cmp);
+                let mid = values.len() / 2;
+                match values.len() {
+                    0 => f64::NAN,
+                    len if len.is_multiple_of(2) => (values[mid - 1] + values[mid]) / 2.,
+                    _ => values[mid],
+                }
+            }
+        }
+    }
+}
+
+/// An aggregation for [`GroupBy::agg`], named after its column unless aliased.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Agg {
+    op: AggOp,
+    column: Option<String>,
+    name: String,
+}
+
+impl Agg {
+    fn new(op: AggOp, column: &str) -> Self {
+        Self {
+            op,
+            column: Some(column.to_string()),
+            name: column.to_string(),
+        }
+    }
+
+    pub fn sum(column: &str) -> Self {
+        Self::new(AggOp::Sum, column)
+    }
+
+    pub fn mean(column: &str) -> Self {
+        Self::new(AggOp::Mean, column)
+    }
+
+    pub fn median(column: &str) -> Self {
+        Self::new(AggOp::Median, column)
+    }
+
+    /// Values in the column that are not NaN.
+    pub fn count(column: &str) -> Self {
+        Self::new(AggOp::Count, column)
+    }
+
+    /// Rows in the group, named `count`.
+    pub fn rows() -> Self {
+        Self {
+            op: AggOp::Count,
+            column: None,
+            name: "count".to_string(),
+        }
+    }
+
+    pub fn alias(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    fn frame() -> DataFrame {
+        DataFrame::new()
+            .with_column("team", texts(&["a", "b", "a", "a", "b"]))
+            .unwrap()
+            .with_column("xg", vec![1., 2., f64::NAN, 4., f64::NAN])
+            .unwrap()
+            .with_column("shots", vec![3i64, 0, 1, 2, 5])
+            .unwrap()
+    }
+
+    #[test]
+    fn aggregations_skip_nan() {
+        let df = frame()
+            .group_by(&["team"])
+            .unwrap()
+            .agg([
+                Agg::sum("xg"),
+                Agg::mean("xg").alias("mean"),
+                Agg::median("xg").alias("median"),
+                Agg::count("xg").alias("count"),
+                Agg::rows().alias("rows"),
+            ])
+            .unwrap();
+
+        assert_eq!(df.texts("team").unwrap(), texts(&["a", "b"]));
+        assert_eq!(df.numbers("xg").unwrap(), [5., 2.]);
+        assert_eq!(df.numbers("mean").unwrap(), [2.5, 2.]);
+        assert_eq!(df.numbers("median").unwrap(), [2.5, 2.]);
+        assert_eq!(df.integers("count").unwrap(), [2, 1]);
+        assert_eq!(df.integers("rows").unwrap(), [3, 2]);
+    }
+
+    #[test]
+    fn empty_groups_have_a_nan_mean_and_zero_sum() {
+        let df = DataFrame::new()
+            .with_column("team", texts(&["a"]))
+            .unwrap()
+            .with_column("xg", vec![f64::NAN])
+            .unwrap()
+            .group_by(&["team"])
+            .unwrap()
+            .agg([
+                Agg::sum("xg"),
+                Agg::mean("xg").alias("mean"),
+                Agg::median("xg").alias("median"),
+            ])
+            .unwrap();
+
+        assert_eq!(df.numbers("xg").unwrap(), [0.]);
+        assert!(df.numbers("mean").unwrap()[0].is_nan());
+        assert!(df.numbers("median").unwrap()[0].is_nan());
+    }
+
+    #[test]
+    fn integer_sums_stay_integers() {
+        let df = frame()
+            .group_by(&["team"])
+            .unwrap()
+            .agg([Agg::sum("shots"), Agg::mean("shots").alias("mean")])
+            .unwrap();
+
+        assert_eq!(df.integers("shots").unwrap(), [6, 5]);
+        assert_eq!(df.numbers("mean").unwrap(), [2., 2.5]);
+    }
+
+    #[test]
+    fn ratios_are_zero_for_a_zero_denominator() {
+        let df = frame().ratio("xg_per_shot", "xg", "shots").unwrap();
+        let ratios = df.numbers("xg_per_shot").unwrap();
+        assert_eq!(ratios[0], 1. / 3.);
+        assert_eq!(ratios[1], 0.);
+    }
+
+    #[test]
+    fn sorts_are_stable_with_nan_last() {
+        let df = frame().sort_by("xg").unwrap();
+        assert_eq!(df.integers("shots").unwrap(), [3, 0, 2, 1, 5]);
+
+        let df = frame().sort_by_desc("shots").unwrap();
+        assert_eq!(df.texts("team").unwrap(), texts(&["b", "a", "a", "a", "b"]));
+    }
+
+    #[test]
+    fn filters_rows() {
+        let df = frame().filter(|row| row.text("team") == Some("b"));
+        assert_eq!(df.len(), 2);
+        assert_eq!(df.integers("shots").unwrap(), [0, 5]);
+    }
+
+    #[test]
+    fn rejects_mismatched_columns() {
+        assert!(frame().with_column("short", vec![1., 2.]).is_err());
+        assert!(frame().numbers("shots").is_err());
+        assert!(frame().numbers("missing").is_err());
+
+        let other = frame().select(&["team", "xg"]).unwrap();
+        assert!(frame().vstack(&other).is_err());
+        assert_eq!(frame().vstack(&frame()).unwrap().len(), 10);
+    }
+
+    #[test]
+    fn writes_whole_numbers_and_nan() {
+        let df = frame().select(&["shots", "xg"]).unwrap();
+        let json = serde_json::to_value(&df).unwrap();
+        assert_eq!(json[0], serde_json::json!({ "shots": 3, "xg": 1.0 }));
+        assert_eq!(json[2]["xg"], serde_json::Value::Null);
+        assert_eq!(df.columns[1].cell(2), "");
+        assert_eq!(df.columns[0].cell(0), "3");
+    }
+}
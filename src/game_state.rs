@@ -0,0 +1,427 @@
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::line_of,
+    store::{MatchPair, MatchRow, MatchStore},
+    Agg, DataFrame, Fixture, MyError, Result, ResultExt, Teams,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum GameState {
+    Winning,
+    Drawing,
+    Losing,
+}
+
+impl GameState {
+    pub const ALL: [GameState; 3] = [Self::Winning, Self::Drawing, Self::Losing];
+
+    /// The state the opponent is in at the same time.
+    pub fn opposite(self) -> Self {
+        match self {
+            Self::Winning => Self::Losing,
+            Self::Drawing => Self::Drawing,
+            Self::Losing => Self::Winning,
+        }
+    }
+}
+
+impl std::fmt::Display for GameState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Winning => "Winning",
+            Self::Drawing => "Drawing",
+            Self::Losing => "Losing",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for GameState {
+    type Err = MyError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "winning" => Ok(Self::Winning),
+            "drawing" => Ok(Self::Drawing),
+            "losing" => Ok(Self::Losing),
+            _ => Err(MyError::UnknownGameState(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct StateValues<T> {
+    pub winning: T,
+    pub drawing: T,
+    pub losing: T,
+}
+
+impl<T> StateValues<T> {
+    pub fn get(&self, state: GameState) -> &T {
+        match state {
+            GameState::Winning => &self.winning,
+            GameState::Drawing => &self.drawing,
+            GameState::Losing => &self.losing,
+        }
+    }
+
+    pub fn map<U, F: FnMut(T) -> U>(self, mut f: F) -> StateValues<U> {
+        StateValues {
+            winning: f(self.winning),
+            drawing: f(self.drawing),
+            losing: f(self.losing),
+        }
+    }
+
+    /// Swaps winning and losing, turning one team's view of a match into the opponent's.
+    pub fn opposite(self) -> Self {
+        Self {
+            winning: self.losing,
+            drawing: self.drawing,
+            losing: self.winning,
+        }
+    }
+}
+
+/// One row of a Team/Match/Winning/Drawing/Losing export.
+///
+/// A `-` or empty cell means the team never reached that state in the match.
+#[derive(Debug, Clone, Serialize)]
+pub struct GameStateRecord<T> {
+    pub row: u64,
+    pub team: Teams,
+    pub fixture: Fixture,
+    pub values: StateValues<Option<T>>,
+}
+
+/// A row joined with the opponent's row of the same match.
+///
+/// `theirs` is already flipped, so `theirs.winning` is the opponent's losing value.
+#[derive(Debug, Clone, Serialize)]
+pub struct PairedRecord<T> {
+    pub team: Teams,
+    pub opponent: Teams,
+    pub fixture: Fixture,
+    pub ours: StateValues<Option<T>>,
+    pub theirs: StateValues<Option<T>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawRecord {
+    #[serde(rename = "Team")]
+    team: Teams,
+    #[serde(rename = "Match")]
+    game: Fixture,
+    #[serde(rename = "Winning")]
+    winning: String,
+    #[serde(rename = "Drawing")]
+    drawing: String,
+    #[serde(rename = "Losing")]
+    losing: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct GameStateSeries<T> {
+    /// The file the records were read from, for error messages.
+    path: Option<PathBuf>,
+    records: Vec<GameStateRecord<T>>,
+}
+
+impl<T: FromStr> GameStateSeries<T> {
+    pub fn from_csv<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let file = std::fs::File::open(path).with_path(path)?;
+        let mut series = Self::from_reader(file).with_path(path)?;
+        series.path = Some(path.to_path_buf());
+        Ok(series)
+    }
+
+    pub fn from_reader<R: io::Read>(reader: R) -> Result<Self> {
+        let mut csv_reader = csv::Reader::from_reader(reader);
+        let headers = csv_reader.headers()?.clone();
+        let mut records = Vec::new();
+
+        for record in csv_reader.records() {
+            let record = record?;
+            let row = record.position().map_or(0, line_of);
+            let raw: RawRecord = record.deserialize(Some(&headers))?;
+            let cell = |column: &'static str, value: &str| -> Result<Option<T>> {
+                match value.trim() {
+                    "" | "-" => Ok(None),
+                    value => value.parse().map(Some).map_err(|_| MyError::InvalidValue {
+                        path: None,
+                        row,
+                        column,
+                        value: value.to_string(),
+                    }),
+                }
+            };
+
+            records.push(GameStateRecord {
+                row,
+                team: raw.team,
+                values: StateValues {
+                    winning: cell("Winning", &raw.winning)?,
+                    drawing: cell("Drawing", &raw.drawing)?,
+                    losing: cell("Losing", &raw.losing)?,
+                },
+                fixture: raw.game,
+            });
+        }
+
+        if records.is_empty() {
+            return Err(MyError::EmptyDataset { path: None });
+        }
+
+        Ok(Self {
+            path: None,
+            records,
+        })
+    }
+}
+
+impl<T: Clone> GameStateSeries<T> {
+    pub fn records(&self) -> &[GameStateRecord<T>] {
+        &self.records
+    }
+
+    pub fn retain<F: FnMut(&GameStateRecord<T>) -> bool>(&mut self, f: F) {
+        self.records.retain(f);
+    }
+
+    /// Teams in order of first appearance.
+    pub fn teams(&self) -> Vec<Teams> {
+        let mut teams = Vec::new();
+        for record in &self.records {
+            if !teams.contains(&record.team) {
+                teams.push(record.team);
+            }
+        }
+        teams
+    }
+
+    fn store(&self) -> MatchStore<&GameStateRecord<T>> {
+        MatchStore::new(self.records.iter().collect())
+    }
+
+    /// Joins every row with the opponent's row of the same fixture.
+    pub fn paired(&self) -> Result<Vec<PairedRecord<T>>> {
+        self.store()
+            .paired()
+            .map(|pair| {
+                let MatchPair {
+                    ours,
+                    theirs,
+                    opponent,
+                    ..
+                } = pair.map_err(|err| match &self.path {
+                    Some(path) => err.with_path(path),
+                    None => err,
+                })?;
+
+                Ok(PairedRecord {
+                    team: ours.team,
+                    opponent,
+                    fixture: ours.fixture.clone(),
+                    ours: ours.values.clone(),
+                    theirs: theirs.values.clone().opposite(),
+                })
+            })
+            .collect()
+    }
+}
+
+impl<T> MatchRow for GameStateRecord<T> {
+    fn team(&self) -> Teams {
+        self.team
+    }
+
+    fn fixture(&self) -> &Fixture {
+        &self.fixture
+    }
+
+    fn row(&self) -> u64 {
+        self.row
+    }
+}
+
+/// A per-match metric split into parts, so it can be averaged or summed.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Ratio {
+    pub numerator: f64,
+    pub denominator: f64,
+}
+
+impl Ratio {
+    pub fn new(numerator: f64, denominator: f64) -> Self {
+        Self {
+            numerator,
+            denominator,
+        }
+    }
+
+    /// The ratio, or zero when the denominator is zero.
+    pub fn value(&self) -> f64 {
+        if self.denominator == 0. {
+            0.
+        } else {
+            self.numerator / self.denominator
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Aggregation<'a> {
+    /// Mean of the per-match values.
+    Mean,
+    /// Mean of the per-match values weighted by another export with the same layout,
+    /// e.g. minutes spent in each state.
+    WeightedMean(&'a GameStateSeries<f64>),
+    /// Sum of numerators over sum of denominators.
+    RatioOfSums,
+}
+
+/// Aggregates paired records per team and state, in order of first appearance.
+///
+/// `metric` is called with our and the opponent's value for the same state and returns
+/// `None` when the match should not count for that state. With [`Aggregation::Mean`] the
+/// frame has the columns `team`, `state`, the number of `matches` and their mean `value`.
+/// Otherwise it has `team`, `state`, the summed `numerator` and `denominator` and their
+/// ratio `value`. States a team never reached, or only with a zero denominator, have no
+/// row.
+pub fn aggregate<'a, T, I, F>(
+    pairs: I,
+    aggregation: Aggregation<'_>,
+    metric: F,
+) -> Result<DataFrame>
+where
+    T: 'a,
+    I: IntoIterator<Item = &'a PairedRecord<T>>,
+    F: Fn(Option<&T>, Option<&T>) -> Option<Ratio>,
+{
+    let weights = match aggregation {
+        Aggregation::WeightedMean(weights) => Some(weights.store()),
+        _ => None,
+    };
+    let (mut teams, mut states, mut numerators, mut denominators) =
+        (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+
+    for pair in pairs {
+        for state in GameState::ALL {
+            let Some(ratio) = metric(
+                pair.ours.get(state).as_ref(),
+                pair.theirs.get(state).as_ref(),
+            ) else {
+                continue;
+            };
+            let (numerator, denominator) = match aggregation {
+                Aggregation::Mean => (ratio.value(), 1.),
+                Aggregation::WeightedMean(_) => {
+                    let weight = weights
+                        .as_ref()
+                        .and_then(|weights| weights.get(&pair.fixture, pair.team))
+                        .and_then(|record| *record.values.get(state));
+                    match weight {
+                        Some(weight) => (ratio.value() * weight, weight),
+                        None => continue,
+                    }
+                }
+                Aggregation::RatioOfSums => (ratio.numerator, ratio.denominator),
+            };
+
+            teams.push(pair.team);
+            states.push(state.to_string());
+            numerators.push(numerator);
+            denominators.push(denominator);
+        }
+    }
+
+    let df = DataFrame::new()
+        .with_column("team", teams)?
+        .with_column("state", states)?;
+    match aggregation {
+        Aggregation::Mean => df
+            .with_column("value", numerators)?
+            .group_by(&["team", "state"])?
+            .agg([Agg::rows().alias("matches"), Agg::mean("value")]),
+        _ => df
+            .with_column("numerator", numerators)?
+            .with_column("denominator", denominators)?
+            .group_by(&["team", "state"])?
+            .agg([Agg::sum("numerator"), Agg::sum("denominator")])?
+            .filter(|row| row.number("denominator") != Some(0.))
+            .ratio("value", "numerator", "denominator"),
+    }
+}
+
+/// Aggregates laid out per state, keyed by team, with a fixed team order for charts.
+///
+/// States a team never reached are left out of the maps.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StateFrame {
+    pub teams: Vec<Teams>,
+    pub winning: HashMap<Teams, f64>,
+    pub drawing: HashMap<Teams, f64>,
+    pub losing: HashMap<Teams, f64>,
+}
+
+impl StateFrame {
+    /// Lays out the `value` column of an [`aggregate`] frame.
+    pub fn new(teams: Vec<Teams>, aggregates: &DataFrame) -> Result<Self> {
+        let mut frame = Self {
+            teams,
+            ..Default::default()
+        };
+
+        let rows = aggregates
+            .teams("team")?
+            .iter()
+            .zip(aggregates.texts("state")?)
+            .zip(aggregates.numbers("value")?);
+        for ((team, state), value) in rows {
+            frame.state_mut(state.parse()?).insert(*team, *value);
+        }
+
+        Ok(frame)
+    }
+
+    pub fn get(&self, state: GameState) -> &HashMap<Teams, f64> {
+        match state {
+            GameState::Winning => &self.winning,
+            GameState::Drawing => &self.drawing,
+            GameState::Losing => &self.losing,
+        }
+    }
+
+    fn state_mut(&mut self, state: GameState) -> &mut HashMap<Teams, f64> {
+        match state {
+            GameState::Winning => &mut self.winning,
+            GameState::Drawing => &mut self.drawing,
+            GameState::Losing => &mut self.losing,
+        }
+    }
+
+    /// Mean over the states each team reached.
+    pub fn state_mean(&self) -> HashMap<Teams, f64> {
+        self.teams
+            .iter()
+            .filter_map(|team| {
+                let values = GameState::ALL
+                    .iter()
+                    .filter_map(|state| self.get(*state).get(team))
+                    .collect::<Vec<_>>();
+                if values.is_empty() {
+                    return None;
+                }
+                let mean = values.iter().copied().sum::<f64>() / values.len() as f64;
+                Some((*team, mean))
+            })
+            .collect()
+    }
+}
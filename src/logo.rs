@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use image::{DynamicImage, ImageReader};
+
+use crate::{MyError, Result, ResultExt, Teams};
+
+const EXTENSIONS: [&str; 4] = ["png", "jpg", "jpeg", "webp"];
+
+static LOGOS: OnceLock<LogoStore> = OnceLock::new();
+
+/// Returns the installed logo store, falling back to the paths in the team registry.
+pub fn logos() -> &'static LogoStore {
+    LOGOS.get_or_init(LogoStore::default)
+}
+
+/// Loads club logos once and keeps them in memory.
+///
+/// A logo is looked up at the team's registry path, or under the same file name in the
+/// store's directory when one is set. If that file is missing, the other supported
+/// extensions are tried, so `AREMA FC.webp` is found for a registered `AREMA FC.png`.
+#[derive(Debug, Default)]
+pub struct LogoStore {
+    dir: Option<PathBuf>,
+    cache: Mutex<HashMap<Teams, Option<Arc<DynamicImage>>>>,
+}
+
+impl LogoStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads logos from `dir` instead of the registry's `logo_dir`.
+    pub fn dir<P: Into<PathBuf>>(mut self, dir: P) -> Self {
+        self.dir = Some(dir.into());
+        self
+    }
+
+    pub fn install(self) -> Result<()> {
+        LOGOS.set(self).map_err(|_| {
+            MyError::Registry("Cannot install logos, a logo store is already in use".to_string())
+        })
+    }
+
+    /// The team's logo, or `None` when there is no file for it. A file that exists but
+    /// cannot be decoded is an error.
+    pub fn get(&self, team: Teams) -> Result<Option<Arc<DynamicImage>>> {
+        if let Some(logo) = self.cache.lock().expect("logo cache").get(&team) {
+            return Ok(logo.clone());
+        }
+
+        let path = self
+            .candidates(team)
+            .into_iter()
+            .find(|path| path.is_file());
+        let logo = path
+            .map(|path| load(&path).with_path(&path).map(Arc::new))
+            .transpose()?;
+        self.cache
+            .lock()
+            .expect("logo cache")
+            .insert(team, logo.clone());
+
+        Ok(logo)
+    }
+
+    fn candidates(&self, team: Teams) -> Vec<PathBuf> {
+        let registered = &team.info().logo;
+        let path = match (&self.dir, registered.file_name()) {
+            (Some(dir), Some(file_name)) => dir.join(file_name),
+            _ => registered.clone(),
+        };
+
+        std::iter::once(path.clone())
+            .chain(EXTENSIONS.iter().map(|ext| path.with_extension(ext)))
+            .collect()
+    }
+}
+
+/// Decodes by content rather than extension, as exported logos are often mislabelled.
+fn load(path: &Path) -> Result<DynamicImage> {
+    Ok(ImageReader::open(path)?.with_guessed_format()?.decode()?)
+}
@@ -0,0 +1,19 @@
+use crate::game_state::Ratio;
+
+/// Share of final-third touches while both teams were in the matching states.
+///
+/// Matches where neither side touched the ball in the final third count as zero.
+pub fn field_tilt(ours: Option<&f64>, theirs: Option<&f64>) -> Option<Ratio> {
+    let (ours, theirs) = (*ours?, *theirs?);
+    Some(Ratio::new(ours, ours + theirs))
+}
+
+/// Passes allowed per defensive action, skipping matches where the state was not reached.
+///
+/// The export marks an unreached state with `-` or with `0`: a team that defended at all
+/// allowed at least one pass, so a PPDA of zero only shows up where the state never
+/// happened and there were no touches in it either.
+pub fn ppda(ours: Option<&f64>, _theirs: Option<&f64>) -> Option<Ratio> {
+    ours.filter(|ppda| **ppda > 0.)
+        .map(|ppda| Ratio::new(*ppda, 1.))
+}
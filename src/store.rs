@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+
+use crate::{Fixture, MyError, Result, Teams, Venue};
+
+/// A row of a per-team match export, one per team and fixture.
+pub trait MatchRow {
+    fn team(&self) -> Teams;
+    fn fixture(&self) -> &Fixture;
+    /// Line in the source file, for error messages.
+    fn row(&self) -> u64;
+}
+
+impl<R: MatchRow> MatchRow for &R {
+    fn team(&self) -> Teams {
+        (*self).team()
+    }
+
+    fn fixture(&self) -> &Fixture {
+        (*self).fixture()
+    }
+
+    fn row(&self) -> u64 {
+        (*self).row()
+    }
+}
+
+/// Rows indexed by `(fixture, team)`, so every row finds its opponent's without a scan.
+///
+/// Each fixture keeps a slot for the home and the away team. When a team has several rows
+/// for one fixture the first is indexed, and rows for a team that does not play in the
+/// fixture are not indexed at all.
+#[derive(Debug, Clone)]
+pub struct MatchStore<R> {
+    rows: Vec<R>,
+    index: HashMap<Fixture, [Option<usize>; 2]>,
+}
+
+/// A row joined with the opponent's row of the same fixture.
+#[derive(Debug, Clone, Copy)]
+pub struct MatchPair<'a, R> {
+    pub ours: &'a R,
+    pub theirs: &'a R,
+    pub opponent: Teams,
+    pub venue: Venue,
+}
+
+fn slot(venue: Venue) -> usize {
+    match venue {
+        Venue::Home => 0,
+        Venue::Away => 1,
+    }
+}
+
+impl<R: MatchRow> MatchStore<R> {
+    pub fn new(rows: Vec<R>) -> Self {
+        let mut index: HashMap<Fixture, [Option<usize>; 2]> = HashMap::with_capacity(rows.len());
+        for (idx, row) in rows.iter().enumerate() {
+            if let Some(venue) = row.fixture().venue_of(row.team()) {
+                if let Some(slots) = index.get_mut(row.fixture()) {
+                    slots[slot(venue)].get_or_insert(idx);
+                } else {
+                    let mut slots = [None; 2];
+                    slots[slot(venue)] = Some(idx);
+                    index.insert(row.fixture().clone(), slots);
+                }
+            }
+        }
+
+        Self { rows, index }
+    }
+
+    pub fn rows(&self) -> &[R] {
+        &self.rows
+    }
+
+    pub fn into_rows(self) -> Vec<R> {
+        self.rows
+    }
+
+    /// The team's row for `fixture`.
+    pub fn get(&self, fixture: &Fixture, team: Teams) -> Option<&R> {
+        let venue = fixture.venue_of(team)?;
+        let idx = self.index.get(fixture)?[slot(venue)]?;
+        Some(&self.rows[idx])
+    }
+
+    /// Joins a row with its opponent's row.
+    pub fn pair<'a>(&'a self, row: &'a R) -> Result<MatchPair<'a, R>> {
+        let unmatched = |opponent| MyError::UnmatchedFixture {
+            path: None,
+            row: row.row(),
+            team: row.team(),
+            fixture: row.fixture().clone(),
+            opponent,
+        };
+        let fixture = row.fixture();
+        let venue = fixture
+            .venue_of(row.team())
+            .ok_or_else(|| unmatched(None))?;
+        let opponent = match venue {
+            Venue::Home => fixture.away,
+            Venue::Away => fixture.home,
+        };
+        let theirs = self
+            .get(fixture, opponent)
+            .ok_or_else(|| unmatched(Some(opponent)))?;
+
+        Ok(MatchPair {
+            ours: row,
+            theirs,
+            opponent,
+            venue,
+        })
+    }
+
+    /// Every row joined with its opponent's, in file order.
+    pub fn paired(&self) -> impl Iterator<Item = Result<MatchPair<'_, R>>> + '_ {
+        self.rows.iter().map(|row| self.pair(row))
+    }
+}
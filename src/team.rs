@@ -0,0 +1,482 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+use plotters::style::RGBColor;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{logos, MyError, Result, ResultExt};
+
+const DEFAULT_REGISTRY: &str = include_str!("../teams/liga1_2024-25.toml");
+/// Where the bundled registry lives, so its `logo_dir` resolves as if it were passed as a
+/// file.
+const DEFAULT_REGISTRY_DIR: &str = "teams";
+
+static REGISTRY: OnceLock<TeamRegistry> = OnceLock::new();
+
+/// Returns the installed team registry, falling back to the bundled Liga 1 2024-25 file.
+pub fn registry() -> &'static TeamRegistry {
+    REGISTRY.get_or_init(|| {
+        TeamRegistry::from_toml(DEFAULT_REGISTRY, Path::new(DEFAULT_REGISTRY_DIR))
+            .expect("bundled team registry is valid")
+    })
+}
+
+/// Handle to a team in the installed [`TeamRegistry`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Teams(u16);
+
+impl Teams {
+    pub fn info(&self) -> &'static TeamInfo {
+        &registry().teams[self.0 as usize]
+    }
+
+    pub fn name(&self) -> &'static str {
+        &self.info().name
+    }
+
+    pub fn short_code(&self) -> &'static str {
+        &self.info().short
+    }
+
+    pub fn all() -> impl Iterator<Item = Teams> {
+        (0..registry().teams.len()).map(|idx| Teams(idx as u16))
+    }
+
+    /// The club logo from the installed [`LogoStore`](crate::LogoStore), `None` when
+    /// there is no file for it.
+    pub fn logo(&self) -> Result<Option<Arc<image::DynamicImage>>> {
+        logos().get(*self)
+    }
+}
+
+impl std::fmt::Display for Teams {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl std::fmt::Debug for Teams {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Teams({:?})", self.name())
+    }
+}
+
+/// As the registry name, like it is read.
+impl Serialize for Teams {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for Teams {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        name.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// The primary colour. Charts with several teams use a
+/// [`Palette`](crate::chart::Palette), which keeps clashing clubs apart.
+impl From<Teams> for RGBColor {
+    fn from(value: Teams) -> Self {
+        value.info().primary
+    }
+}
+
+impl From<&Teams> for RGBColor {
+    fn from(value: &Teams) -> Self {
+        value.info().primary
+    }
+}
+
+impl std::str::FromStr for Teams {
+    type Err = MyError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        registry().parse(s)
+    }
+}
+
+impl TryFrom<&str> for Teams {
+    type Error = MyError;
+
+    fn try_from(value: &str) -> Result<Self> {
+        value.parse()
+    }
+}
+
+impl TryFrom<String> for Teams {
+    type Error = MyError;
+
+    fn try_from(value: String) -> Result<Self> {
+        value.parse()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TeamInfo {
+    pub name: String,
+    pub short: String,
+    pub aliases: Vec<String>,
+    pub primary: RGBColor,
+    pub secondary: RGBColor,
+    pub logo: PathBuf,
+}
+
+#[derive(Debug, Deserialize)]
+struct RegistryFile {
+    league: String,
+    #[serde(default)]
+    logo_dir: PathBuf,
+    #[serde(rename = "team")]
+    teams: Vec<TeamRecord>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TeamRecord {
+    name: String,
+    short: String,
+    #[serde(default)]
+    aliases: Vec<String>,
+    primary: String,
+    secondary: String,
+    logo: Option<PathBuf>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CsvTeamRecord {
+    name: String,
+    short: String,
+    #[serde(default)]
+    aliases: String,
+    primary: String,
+    secondary: String,
+    logo: Option<PathBuf>,
+}
+
+/// Teams of one league season, loaded from a TOML or CSV file.
+///
+/// TOML files hold a `league`, an optional `logo_dir` and one `[[team]]` table per club.
+/// CSV files use the columns `name,short,aliases,primary,secondary,logo`, with aliases
+/// separated by `;`. Logos default to `{logo_dir}/{name}.png`. A relative `logo_dir`, and
+/// the directory of a CSV file, are relative to the registry file.
+///
+/// Names are matched ignoring case and repeated whitespace, against the canonical name,
+/// the short code and every alias.
+#[derive(Debug)]
+pub struct TeamRegistry {
+    league: String,
+    teams: Vec<TeamInfo>,
+    lookup: HashMap<String, Teams>,
+}
+
+impl TeamRegistry {
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => {
+                let base = path.parent().unwrap_or(Path::new(""));
+                Self::from_toml(&fs::read_to_string(path).with_path(path)?, base).with_path(path)
+            }
+            Some("csv") => {
+                let league = path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                let logo_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+                Self::from_csv_reader(fs::File::open(path).with_path(path)?, league, logo_dir)
+                    .with_path(path)
+            }
+            _ => Err(MyError::Registry(format!(
+                "Unsupported team registry format: {}",
+                path.display()
+            ))),
+        }
+    }
+
+    /// Parses a TOML registry whose relative `logo_dir` is resolved against the working
+    /// directory.
+    pub fn from_toml_str(input: &str) -> Result<Self> {
+        Self::from_toml(input, Path::new(""))
+    }
+
+    fn from_toml(input: &str, base: &Path) -> Result<Self> {
+        let file: RegistryFile = toml::from_str(input)?;
+        let logo_dir = base.join(&file.logo_dir);
+        let teams = file
+            .teams
+            .into_iter()
+            .map(|record| {
+                TeamInfo::new(
+                    record.name,
+                    record.short,
+                    record.aliases,
+                    &record.primary,
+                    &record.secondary,
+                    record.logo,
+                    &logo_dir,
+                )
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Self::new(file.league, teams)
+    }
+
+    pub fn from_csv_reader<R: io::Read>(
+        reader: R,
+        league: String,
+        logo_dir: PathBuf,
+    ) -> Result<Self> {
+        let mut csv_reader = csv::Reader::from_reader(reader);
+        let mut teams = Vec::new();
+
+        for record in csv_reader.deserialize() {
+            let record: CsvTeamRecord = record?;
+            let aliases = record
+                .aliases
+                .split(';')
+                .map(str::trim)
+                .filter(|alias| !alias.is_empty())
+                .map(String::from)
+                .collect();
+            teams.push(TeamInfo::new(
+                record.name,
+                record.short,
+                aliases,
+                &record.primary,
+                &record.secondary,
+                record.logo,
+                &logo_dir,
+            )?);
+        }
+
+        Self::new(league, teams)
+    }
+
+    fn new(league: String, teams: Vec<TeamInfo>) -> Result<Self> {
+        if teams.len() > u16::MAX as usize {
+            return Err(MyError::Registry(format!("Too many teams in {league}")));
+        }
+
+        let mut lookup = HashMap::new();
+        for (idx, info) in teams.iter().enumerate() {
+            let team = Teams(idx as u16);
+            let names = [&info.name, &info.short].into_iter().chain(&info.aliases);
+            for name in names {
+                if let Some(other) = lookup.insert(normalize(name), team) {
+                    if other != team {
+                        return Err(MyError::Registry(format!(
+                            "Team name {name} is used by more than one team in {league}"
+                        )));
+                    }
+                }
+            }
+        }
+
+        Ok(Self {
+            league,
+            teams,
+            lookup,
+        })
+    }
+
+    /// Makes this the registry every [`Teams`] handle resolves against.
+    ///
+    /// Must be called before any team name is parsed.
+    pub fn install(self) -> Result<()> {
+        let league = self.league.clone();
+        REGISTRY.set(self).map_err(|_| {
+            MyError::Registry(format!(
+                "Cannot install {league}, a team registry is already in use"
+            ))
+        })
+    }
+
+    pub fn league(&self) -> &str {
+        &self.league
+    }
+
+    pub fn len(&self) -> usize {
+        self.teams.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.teams.is_empty()
+    }
+
+    pub fn resolve(&self, name: &str) -> Option<Teams> {
+        self.lookup.get(&normalize(name)).copied()
+    }
+
+    /// Resolves `name`, or fails with the closest known team as a suggestion.
+    pub fn parse(&self, name: &str) -> Result<Teams> {
+        self.resolve(name).ok_or_else(|| MyError::UnknownTeam {
+            name: name.to_string(),
+            suggestion: self.suggest(name),
+        })
+    }
+
+    /// Returns the team whose name or alias is closest to `name` by edit distance,
+    /// as long as fewer than half of the characters differ.
+    pub fn suggest(&self, name: &str) -> Option<Teams> {
+        let name = normalize(name);
+        self.lookup
+            .iter()
+            .map(|(known, team)| (edit_distance(&name, known), *team))
+            .filter(|(distance, _)| *distance * 2 < name.chars().count().max(1))
+            .min_by_key(|(distance, team)| (*distance, *team))
+            .map(|(_, team)| team)
+    }
+}
+
+impl TeamInfo {
+    fn new(
+        name: String,
+        short: String,
+        aliases: Vec<String>,
+        primary: &str,
+        secondary: &str,
+        logo: Option<PathBuf>,
+        logo_dir: &Path,
+    ) -> Result<Self> {
+        let logo = logo.unwrap_or_else(|| PathBuf::from(format!("{name}.png")));
+        Ok(Self {
+            primary: parse_hex_color(primary)?,
+            secondary: parse_hex_color(secondary)?,
+            logo: logo_dir.join(logo),
+            name,
+            short,
+            aliases,
+        })
+    }
+}
+
+fn normalize(name: &str) -> String {
+    name.split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut prev = (0..=b.len()).collect::<Vec<_>>();
+    let mut curr = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != *cb);
+            curr[j + 1] = substitution.min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b.len()]
+}
+
+fn parse_hex_color(input: &str) -> Result<RGBColor> {
+    let invalid = || MyError::Registry(format!("Invalid colour {input}, expected #RRGGBB"));
+    let hex = input.trim().strip_prefix('#').ok_or_else(invalid)?;
+    if hex.len() != 6 || !hex.is_ascii() {
+        return Err(invalid());
+    }
+    let channel = |idx: usize| u8::from_str_radix(&hex[idx..idx + 2], 16).map_err(|_| invalid());
+
+    Ok(RGBColor(channel(0)?, channel(2)?, channel(4)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bundled() -> TeamRegistry {
+        TeamRegistry::from_toml_str(DEFAULT_REGISTRY).unwrap()
+    }
+
+    #[test]
+    fn resolves_ignoring_case_and_whitespace() {
+        let teams = bundled();
+        let arema = teams.resolve("AREMA FC").unwrap();
+        assert_eq!(teams.resolve("Arema FC"), Some(arema));
+        assert_eq!(teams.resolve("  arema   fc "), Some(arema));
+        assert_eq!(
+            teams.resolve("Dewa  United FC"),
+            teams.resolve("DEWA UNITED FC")
+        );
+    }
+
+    #[test]
+    fn resolves_aliases_and_short_codes() {
+        let teams = bundled();
+        let persib = teams.resolve("PERSIB Bandung").unwrap();
+        assert_eq!(teams.resolve("Persib"), Some(persib));
+        assert_eq!(teams.resolve(&persib.info().short), Some(persib));
+        assert_eq!(teams.resolve("Persebaya Surabaya FC"), None);
+    }
+
+    #[test]
+    fn suggests_the_closest_team() {
+        let teams = bundled();
+        assert_eq!(teams.suggest("Persk"), teams.resolve("PERSIK Kediri"));
+        assert_eq!(
+            teams.suggest("Bali Unted FC"),
+            teams.resolve("Bali United FC")
+        );
+        assert_eq!(teams.suggest("Manchester City"), None);
+
+        match teams.parse("Persk Kediri") {
+            Err(MyError::UnknownTeam { name, suggestion }) => {
+                assert_eq!(name, "Persk Kediri");
+                assert_eq!(suggestion, teams.resolve("PERSIK Kediri"));
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+
+    #[test]
+    fn rejects_names_shared_by_two_teams() {
+        let toml = r##"
+            league = "Test"
+
+            [[team]]
+            name = "United"
+            short = "UTD"
+            primary = "#000000"
+            secondary = "#ffffff"
+
+            [[team]]
+            name = "City"
+            short = "CTY"
+            aliases = ["united"]
+            primary = "#000000"
+            secondary = "#ffffff"
+        "##;
+        assert!(matches!(
+            TeamRegistry::from_toml_str(toml),
+            Err(MyError::Registry(_))
+        ));
+    }
+
+    #[test]
+    fn reads_csv_registries() {
+        let csv = "name,short,aliases,primary,secondary,logo\n\
+                   Sun-Ray FC,SRY,Sun Ray; Rays,#112233,#FFFFFF,\n";
+        let teams =
+            TeamRegistry::from_csv_reader(csv.as_bytes(), "Test".into(), "logos".into()).unwrap();
+        let team = teams.resolve("rays").unwrap();
+        assert_eq!(teams.resolve("sun ray"), Some(team));
+        assert_eq!(teams.teams[0].primary, RGBColor(0x11, 0x22, 0x33));
+        assert_eq!(teams.teams[0].logo, Path::new("logos/Sun-Ray FC.png"));
+    }
+
+    #[test]
+    fn rejects_invalid_colours() {
+        for colour in ["112233", "#12345", "#GG0000", "#1122334"] {
+            assert!(parse_hex_color(colour).is_err(), "{colour}");
+        }
+    }
+}
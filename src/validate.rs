@@ -0,0 +1,303 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    corners::DataSource, error::line_of, Fixture, MatchRow, MatchStore, Result, ResultExt, Teams,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Suspicious, but the charts can still be drawn.
+    Warning,
+    /// The export is wrong and would give wrong or no charts.
+    Error,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Warning => write!(f, "warning"),
+            Self::Error => write!(f, "error"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Issue {
+    pub severity: Severity,
+    /// CSV line, or `None` for issues about the file as a whole.
+    pub row: Option<u64>,
+    pub message: String,
+}
+
+/// Everything found wrong with one input file.
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub path: PathBuf,
+    pub issues: Vec<Issue>,
+}
+
+impl Report {
+    fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            issues: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, severity: Severity, row: Option<u64>, message: impl Into<String>) {
+        self.issues.push(Issue {
+            severity,
+            row,
+            message: message.into(),
+        });
+    }
+
+    fn error(&mut self, row: u64, message: impl Into<String>) {
+        self.push(Severity::Error, Some(row), message);
+    }
+
+    fn warning(&mut self, row: u64, message: impl Into<String>) {
+        self.push(Severity::Warning, Some(row), message);
+    }
+
+    pub fn count(&self, severity: Severity) -> usize {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == severity)
+            .count()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.count(Severity::Error) > 0
+    }
+}
+
+impl std::fmt::Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (errors, warnings) = (self.count(Severity::Error), self.count(Severity::Warning));
+        writeln!(
+            f,
+            "{}: {errors} error(s), {warnings} warning(s)",
+            self.path.display()
+        )?;
+
+        let mut issues = self.issues.iter().collect::<Vec<_>>();
+        issues.sort_by_key(|issue| (issue.row, std::cmp::Reverse(issue.severity)));
+        for issue in issues {
+            match issue.row {
+                Some(row) => writeln!(f, "  {:<7} line {row}: {}", issue.severity, issue.message)?,
+                None => writeln!(f, "  {:<7} {}", issue.severity, issue.message)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn read_rows<T, F>(path: &Path, report: &mut Report, mut parse: F) -> Result<Vec<T>>
+where
+    F: FnMut(&csv::StringRecord, &csv::StringRecord, u64) -> std::result::Result<T, String>,
+{
+    let mut reader = csv::Reader::from_path(path).with_path(path)?;
+    let headers = reader.headers().with_path(path)?.clone();
+    let mut rows = Vec::new();
+
+    for record in reader.records() {
+        let record = match record {
+            Ok(record) => record,
+            Err(err) => {
+                let row = err.position().map(line_of);
+                report.push(Severity::Error, row, err.to_string());
+                continue;
+            }
+        };
+        let row = record.position().map_or(0, line_of);
+        match parse(&headers, &record, row) {
+            Ok(parsed) => rows.push(parsed),
+            Err(err) => report.error(row, err),
+        }
+    }
+
+    Ok(rows)
+}
+
+/// Checks a corner export (`team,game,game_week,total_ck_for,xg,shots_from_ck`).
+pub fn validate_corners<P: AsRef<Path>>(path: P) -> Result<Report> {
+    let path = path.as_ref();
+    let mut report = Report::new(path);
+    let rows = read_rows(path, &mut report, |headers, record, row| {
+        let mut ds: DataSource = record
+            .deserialize(Some(headers))
+            .map_err(|err| err.to_string())?;
+        ds.row = row;
+        ds.game.game_week = Some(ds.game_week);
+        Ok(ds)
+    })?;
+
+    let store = MatchStore::new(rows.iter().collect());
+    let mut weeks: HashMap<(u32, Teams), u64> = HashMap::new();
+    for ds in &rows {
+        if ds.shots_from_ck > ds.total_ck_for {
+            report.error(
+                ds.row,
+                format!(
+                    "shots_from_ck ({}) is more than total_ck_for ({})",
+                    ds.shots_from_ck, ds.total_ck_for
+                ),
+            );
+        }
+        if ds.xg.is_nan() || ds.xg < 0. {
+            report.error(ds.row, format!("xg ({}) must be zero or more", ds.xg));
+        } else if ds.shots_from_ck == 0 && ds.xg > 0. {
+            report.warning(
+                ds.row,
+                format!("xg ({}) without a shot from a corner", ds.xg),
+            );
+        } else if ds.xg > ds.shots_from_ck as f64 {
+            report.warning(
+                ds.row,
+                format!(
+                    "xg ({}) is more than 1 per shot ({} shots)",
+                    ds.xg, ds.shots_from_ck
+                ),
+            );
+        }
+
+        if let Some(first) = weeks.insert((ds.game_week, ds.team), ds.row) {
+            report.error(
+                ds.row,
+                format!(
+                    "{} already plays in game week {} on line {first}",
+                    ds.team, ds.game_week
+                ),
+            );
+        }
+
+        check_opponent(&mut report, ds.row, ds.team, &ds.game, |opponent| {
+            store.get(&ds.game, opponent).is_some()
+        });
+    }
+
+    let teams = rows.iter().map(|ds| ds.team).collect::<BTreeSet<_>>();
+    let game_weeks = rows.iter().map(|ds| ds.game_week).collect::<BTreeSet<_>>();
+    for gw in &game_weeks {
+        for team in &teams {
+            if !weeks.contains_key(&(*gw, *team)) {
+                report.push(
+                    Severity::Warning,
+                    None,
+                    format!("{team} has no match in game week {gw}"),
+                );
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+fn check_opponent<F>(report: &mut Report, row: u64, team: Teams, fixture: &Fixture, has_row: F)
+where
+    F: Fn(Teams) -> bool,
+{
+    match fixture.opponent_of(team) {
+        None => report.error(row, format!("{team} does not play in {fixture}")),
+        Some(opponent) if !has_row(opponent) => {
+            report.error(row, format!("No {opponent} row for {fixture}"))
+        }
+        Some(_) => {}
+    }
+}
+
+struct StateRow {
+    row: u64,
+    team: Teams,
+    fixture: Fixture,
+    values: [Option<f64>; 3],
+}
+
+impl MatchRow for StateRow {
+    fn team(&self) -> Teams {
+        self.team
+    }
+
+    fn fixture(&self) -> &Fixture {
+        &self.fixture
+    }
+
+    fn row(&self) -> u64 {
+        self.row
+    }
+}
+
+/// Checks a game-state export (`Team,Match,Winning,Drawing,Losing`), where `-` or an
+/// empty cell means the state was not reached.
+pub fn validate_game_state<P: AsRef<Path>>(path: P) -> Result<Report> {
+    let path = path.as_ref();
+    let mut report = Report::new(path);
+    let rows = read_rows(path, &mut report, |headers, record, row| {
+        let column = |name: &str| -> std::result::Result<&str, String> {
+            let idx = headers
+                .iter()
+                .position(|header| header == name)
+                .ok_or_else(|| format!("Missing column {name:?}"))?;
+            record
+                .get(idx)
+                .ok_or_else(|| format!("Missing value for {name:?}"))
+        };
+        let value = |name: &str| -> std::result::Result<Option<f64>, String> {
+            match column(name)?.trim() {
+                "" | "-" => Ok(None),
+                value => value
+                    .parse()
+                    .map(Some)
+                    .map_err(|_| format!("Invalid {name} value {value:?}")),
+            }
+        };
+
+        Ok(StateRow {
+            row,
+            team: column("Team")?.parse().map_err(|err| format!("{err}"))?,
+            fixture: column("Match")?.parse().map_err(|err| format!("{err}"))?,
+            values: [value("Winning")?, value("Drawing")?, value("Losing")?],
+        })
+    })?;
+
+    let store = MatchStore::new(rows.iter().collect());
+    let mut seen: HashMap<(&Fixture, Teams), u64> = HashMap::new();
+    for state_row in &rows {
+        for (name, value) in ["Winning", "Drawing", "Losing"]
+            .iter()
+            .zip(state_row.values)
+        {
+            if let Some(value) = value.filter(|value| value.is_nan() || *value < 0.) {
+                report.error(
+                    state_row.row,
+                    format!("{name} ({value}) must be zero or more"),
+                );
+            }
+        }
+
+        if let Some(first) = seen.insert((&state_row.fixture, state_row.team), state_row.row) {
+            report.error(
+                state_row.row,
+                format!(
+                    "{} already has a row for {} on line {first}",
+                    state_row.team, state_row.fixture
+                ),
+            );
+        }
+
+        check_opponent(
+            &mut report,
+            state_row.row,
+            state_row.team,
+            &state_row.fixture,
+            |opponent| store.get(&state_row.fixture, opponent).is_some(),
+        );
+    }
+
+    Ok(report)
+}